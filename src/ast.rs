@@ -1,82 +1,679 @@
+use super::*;
+
+const KEYWORDS: [&str; 6] =
+  ["digraph", "edge", "graph", "node", "strict", "subgraph"];
+
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Attribute<'src> {
   pub key: Id<'src>,
+  pub span: Range<usize>,
   pub value: Option<Id<'src>>,
 }
 
+impl Attribute<'_> {
+  /// Copy any borrowed text, so the attribute no longer borrows the source.
+  #[must_use]
+  pub fn into_owned(self) -> Attribute<'static> {
+    Attribute {
+      key: self.key.into_owned(),
+      span: self.span,
+      value: self.value.map(Id::into_owned),
+    }
+  }
+}
+
+impl Display for Attribute<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.key)?;
+
+    if let Some(value) = &self.value {
+      write!(f, "={value}")?;
+    }
+
+    Ok(())
+  }
+}
+
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct AttributeStatement<'src> {
   pub attributes: Vec<Attribute<'src>>,
+  pub span: Range<usize>,
   pub target: AttributeTarget,
 }
 
+impl AttributeStatement<'_> {
+  #[must_use]
+  pub fn into_owned(self) -> AttributeStatement<'static> {
+    AttributeStatement {
+      attributes: owned_attributes(self.attributes),
+      span: self.span,
+      target: self.target,
+    }
+  }
+}
+
+impl Display for AttributeStatement<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{} ", self.target)?;
+    write_attributes(f, &self.attributes)
+  }
+}
+
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum AttributeTarget {
   Edge,
   Graph,
   Node,
 }
 
+impl Display for AttributeTarget {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self {
+      AttributeTarget::Edge => write!(f, "edge"),
+      AttributeTarget::Graph => write!(f, "graph"),
+      AttributeTarget::Node => write!(f, "node"),
+    }
+  }
+}
+
+/// A single `edge_op (node_id | subgraph)` step of an edge statement.
+///
+/// The span starts at the edge operator and ends at the end of the target.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Edge<'src> {
+  pub operation: EdgeOperation,
+  pub span: Range<usize>,
+  pub target: EdgeTarget<'src>,
+}
+
+impl Edge<'_> {
+  #[must_use]
+  pub fn into_owned(self) -> Edge<'static> {
+    Edge {
+      operation: self.operation,
+      span: self.span,
+      target: self.target.into_owned(),
+    }
+  }
+}
+
+impl Display for Edge<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{} {}", self.operation, self.target)
+  }
+}
+
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum EdgeOperation {
   Arrow,
   DashDash,
 }
 
+impl Display for EdgeOperation {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self {
+      EdgeOperation::Arrow => write!(f, "->"),
+      EdgeOperation::DashDash => write!(f, "--"),
+    }
+  }
+}
+
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct EdgeStatement<'src> {
   pub attributes: Vec<Attribute<'src>>,
-  pub edges: Vec<(EdgeOperation, EdgeTarget<'src>)>,
+  pub edges: Vec<Edge<'src>>,
   pub from: EdgeTarget<'src>,
+  pub span: Range<usize>,
+}
+
+impl EdgeStatement<'_> {
+  #[must_use]
+  pub fn into_owned(self) -> EdgeStatement<'static> {
+    EdgeStatement {
+      attributes: owned_attributes(self.attributes),
+      edges: self.edges.into_iter().map(Edge::into_owned).collect(),
+      from: self.from.into_owned(),
+      span: self.span,
+    }
+  }
+}
+
+impl Display for EdgeStatement<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.from)?;
+
+    for edge in &self.edges {
+      write!(f, " {edge}")?;
+    }
+
+    if !self.attributes.is_empty() {
+      write!(f, " ")?;
+      write_attributes(f, &self.attributes)?;
+    }
+
+    Ok(())
+  }
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum EdgeTarget<'src> {
   NodeId(NodeId<'src>),
   Subgraph(Subgraph<'src>),
 }
 
+impl EdgeTarget<'_> {
+  #[must_use]
+  pub fn into_owned(self) -> EdgeTarget<'static> {
+    match self {
+      EdgeTarget::NodeId(node_id) => EdgeTarget::NodeId(node_id.into_owned()),
+      EdgeTarget::Subgraph(subgraph) => {
+        EdgeTarget::Subgraph(subgraph.into_owned())
+      }
+    }
+  }
+
+  #[must_use]
+  pub fn span(&self) -> Range<usize> {
+    match self {
+      EdgeTarget::NodeId(node_id) => node_id.span.clone(),
+      EdgeTarget::Subgraph(subgraph) => subgraph.span.clone(),
+    }
+  }
+}
+
+/// Subgraphs used as edge endpoints are written on a single line, so that
+/// the edge statement they belong to stays readable.
+impl Display for EdgeTarget<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self {
+      EdgeTarget::NodeId(node_id) => write!(f, "{node_id}"),
+      EdgeTarget::Subgraph(subgraph) => write_inline_subgraph(f, subgraph),
+    }
+  }
+}
+
+/// A parsed DOT graph, the root of the syntax tree.
+///
+/// To walk or rewrite the tree, implement one of the traits in
+/// [`visit`](crate::visit).
+///
+/// # Serialization
+///
+/// With the `serde` feature enabled, the syntax tree implements serde's
+/// `Serialize` and `Deserialize`. Its JSON shape is stable:
+///
+/// - Structs are objects keyed by field name, with `null` for missing optional
+///   fields.
+/// - Spans are `{"start": ..., "end": ...}` byte ranges.
+/// - An [`Id`] is an object with a `kind`, one of `"ident"`, `"number"`,
+///   `"string"` or `"html_string"`, a `span`, and the raw `value`, without
+///   quotes or angle brackets.
+/// - [`GraphKind`], [`AttributeTarget`] and [`EdgeOperation`] are the snake
+///   case names of their variants, such as `"digraph"`, `"node"` or
+///   `"dash_dash"`.
+/// - A [`Statement`] or [`EdgeTarget`] is an object with a single key, the
+///   snake case name of its variant, such as `"node"` or `"node_id"`, holding
+///   the variant's contents. The contents of [`Statement::Assign`] are a
+///   `[key, value]` array.
+///
+/// For example, `graph { a; x = y }` serializes as:
+///
+/// ```json
+/// {
+///   "id": null,
+///   "kind": "graph",
+///   "span": { "start": 0, "end": 18 },
+///   "statements": [
+///     {
+///       "node": {
+///         "attributes": [],
+///         "id": {
+///           "id": {
+///             "kind": "ident",
+///             "span": { "start": 8, "end": 9 },
+///             "value": "a"
+///           },
+///           "port": null,
+///           "span": { "start": 8, "end": 9 }
+///         },
+///         "span": { "start": 8, "end": 9 }
+///       }
+///     },
+///     {
+///       "assign": [
+///         { "kind": "ident", "span": { "start": 11, "end": 12 }, "value": "x" },
+///         { "kind": "ident", "span": { "start": 15, "end": 16 }, "value": "y" }
+///       ]
+///     }
+///   ],
+///   "strict": false
+/// }
+/// ```
+///
+/// Deserialized identifiers own their values, so a graph can be deserialized
+/// as a `Graph<'static>`.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Graph<'src> {
   pub id: Option<Id<'src>>,
   pub kind: GraphKind,
+  pub span: Range<usize>,
   pub statements: Vec<Statement<'src>>,
   pub strict: bool,
 }
 
+impl Graph<'_> {
+  /// Copy all text borrowed from the source into the graph, so that it can be
+  /// stored, sent to another thread or edited independently of the source.
+  ///
+  /// Spans are kept, and still refer to the source the graph was parsed from.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// let graph = {
+  ///   let src = String::from("digraph { a -> b }");
+  ///   dotgraph::parse(&src).unwrap().into_owned()
+  /// };
+  ///
+  /// assert_eq!(graph.to_string(), "digraph {\n  a -> b;\n}");
+  /// ```
+  #[must_use]
+  pub fn into_owned(self) -> Graph<'static> {
+    Graph {
+      id: self.id.map(Id::into_owned),
+      kind: self.kind,
+      span: self.span,
+      statements: owned_statements(self.statements),
+      strict: self.strict,
+    }
+  }
+}
+
+/// Graphs are printed as canonical DOT: one statement per line, indented by
+/// two spaces per nesting level, with every statement except subgraphs
+/// terminated by a semicolon.
+impl Display for Graph<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    if self.strict {
+      write!(f, "strict ")?;
+    }
+
+    write!(f, "{}", self.kind)?;
+
+    if let Some(id) = &self.id {
+      write!(f, " {id}")?;
+    }
+
+    write!(f, " ")?;
+
+    write_block(f, &self.statements, 0)
+  }
+}
+
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum GraphKind {
   Digraph,
   Graph,
 }
 
+impl Display for GraphKind {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self {
+      GraphKind::Digraph => write!(f, "digraph"),
+      GraphKind::Graph => write!(f, "graph"),
+    }
+  }
+}
+
+/// An identifier, in any of the four forms DOT allows.
+///
+/// `value` is the raw source text of the identifier, without the surrounding
+/// quotes or angle brackets of string and HTML string identifiers. It is
+/// borrowed from the source when parsed, and owned after
+/// [`into_owned`](Id::into_owned).
 #[derive(Clone, Debug, PartialEq)]
-pub enum Id<'src> {
-  HtmlString(&'src str),
-  Ident(&'src str),
-  Number(&'src str),
-  String(&'src str),
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Id<'src> {
+  pub kind: IdKind,
+  pub span: Range<usize>,
+  pub value: Cow<'src, str>,
+}
+
+impl<'src> Id<'src> {
+  /// The value of this identifier as Graphviz sees it.
+  ///
+  /// For quoted strings, `+` concatenation is applied, escaped quotes `\"`
+  /// become `"` and backslash-newline line continuations are removed. Other
+  /// backslash sequences, such as `\n` or `\\`, are left as they are, since
+  /// their meaning depends on the attribute they are used in. The value of
+  /// every other kind of identifier is returned unchanged.
+  ///
+  /// The raw source text stays available in `value`, and is what `Display`
+  /// prints.
+  pub fn decoded(&self) -> Cow<'src, str> {
+    if self.kind != IdKind::String || !self.value.contains(['"', '\\']) {
+      return self.value.clone();
+    }
+
+    let mut decoded = String::with_capacity(self.value.len());
+
+    let mut chars = self.value.chars().peekable();
+
+    while let Some(c) = chars.next() {
+      match c {
+        '\\' => match chars.next() {
+          Some('"') => decoded.push('"'),
+          Some('\n') => {}
+          Some('\r') => {
+            chars.next_if_eq(&'\n');
+          }
+          Some(c) => {
+            decoded.push('\\');
+            decoded.push(c);
+          }
+          None => decoded.push('\\'),
+        },
+        // The closing quote of one part of a concatenation: skip the `+` and
+        // the opening quote of the next part.
+        '"' => {
+          for c in chars.by_ref() {
+            if c == '"' {
+              break;
+            }
+          }
+        }
+        c => decoded.push(c),
+      }
+    }
+
+    Cow::Owned(decoded)
+  }
+
+  /// An HTML string identifier, such as an HTML-like label, written between
+  /// angle brackets.
+  pub fn html(value: impl Into<Cow<'src, str>>) -> Self {
+    Self {
+      kind: IdKind::HtmlString,
+      span: 0..0,
+      value: value.into(),
+    }
+  }
+
+  #[must_use]
+  pub fn into_owned(self) -> Id<'static> {
+    Id {
+      kind: self.kind,
+      span: self.span,
+      value: Cow::Owned(self.value.into_owned()),
+    }
+  }
+
+  fn is_identifier(value: &str) -> bool {
+    let mut chars = value.chars();
+
+    chars
+      .next()
+      .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || !c.is_ascii())
+      && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || !c.is_ascii())
+      && !KEYWORDS
+        .iter()
+        .any(|keyword| value.eq_ignore_ascii_case(keyword))
+  }
+
+  fn is_number(value: &str) -> bool {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+
+    let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+    (!integer.is_empty() || !fraction.is_empty())
+      && integer.chars().all(|c| c.is_ascii_digit())
+      && fraction.chars().all(|c| c.is_ascii_digit())
+  }
+
+  /// An identifier for `value`, for building graphs in code.
+  ///
+  /// Values that lex as an identifier or a number, and are not keywords, are
+  /// written as they are. Any other value becomes a quoted string, with its
+  /// quotes escaped. Other backslash sequences, such as `\n` or `\l`, are left
+  /// for Graphviz to interpret.
+  ///
+  /// The [decoded](Id::decoded) value is `value`, except for a backslash
+  /// before a quote, a newline or the end of the value. DOT has no way to
+  /// write such a backslash on its own, so it is doubled, and decodes as `\\`,
+  /// which Graphviz reads as a single backslash in escaped strings such as
+  /// labels.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use dotgraph::Id;
+  ///
+  /// assert_eq!(Id::new("a1").to_string(), "a1");
+  /// assert_eq!(Id::new("-1.5").to_string(), "-1.5");
+  /// assert_eq!(Id::new("node").to_string(), "\"node\"");
+  /// assert_eq!(Id::new("say \"hi\"").to_string(), r#""say \"hi\"""#);
+  /// assert_eq!(Id::new(r"C:\").decoded(), r"C:\\");
+  /// ```
+  pub fn new(value: impl Into<Cow<'src, str>>) -> Self {
+    let value = value.into();
+
+    let kind = if Self::is_identifier(&value) {
+      IdKind::Ident
+    } else if Self::is_number(&value) {
+      IdKind::Number
+    } else {
+      IdKind::String
+    };
+
+    if kind != IdKind::String || !value.contains(['"', '\\']) {
+      return Self {
+        kind,
+        span: 0..0,
+        value,
+      };
+    }
+
+    let mut escaped = String::with_capacity(value.len() + 2);
+
+    let mut chars = value.chars().peekable();
+
+    while let Some(c) = chars.next() {
+      match c {
+        '"' => escaped.push_str("\\\""),
+        '\\' if matches!(chars.peek(), None | Some('"' | '\n' | '\r')) => {
+          escaped.push_str("\\\\");
+        }
+        c => escaped.push(c),
+      }
+    }
+
+    Self {
+      kind,
+      span: 0..0,
+      value: Cow::Owned(escaped),
+    }
+  }
+
+  /// Parse this identifier's value as a typed attribute value, such as a
+  /// [`Color`](crate::value::Color) or a [`Point`](crate::value::Point).
+  ///
+  /// The raw value is parsed, so that error spans can point into the source
+  /// the identifier was parsed from. To parse a value that relies on escapes
+  /// or concatenation, pass [`Id::decoded`] to [`Value::parse`] instead.
+  pub fn parse<'a, T: Value<'a>>(&'a self) -> Result<T, ParseError> {
+    let start = self.value_start();
+
+    T::parse(&self.value).map_err(|error| {
+      ParseError::new(
+        ErrorKind::InvalidValue,
+        error.message,
+        start + error.span.start..start + error.span.end,
+      )
+    })
+  }
+
+  /// The offset in the source of the start of `value`, after any opening
+  /// quote or angle bracket.
+  pub(crate) fn value_start(&self) -> usize {
+    match self.kind {
+      IdKind::HtmlString | IdKind::String => self.span.start + 1,
+      IdKind::Ident | IdKind::Number => self.span.start,
+    }
+  }
+}
+
+/// Identifiers and numbers that would not lex back as themselves, such as
+/// keywords or values containing spaces, are written as quoted strings.
+impl Display for Id<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self.kind {
+      IdKind::HtmlString => write!(f, "<{}>", self.value),
+      IdKind::Ident if Self::is_identifier(&self.value) => {
+        write!(f, "{}", self.value)
+      }
+      IdKind::Number if Self::is_number(&self.value) => {
+        write!(f, "{}", self.value)
+      }
+      IdKind::String => write!(f, "\"{}\"", self.value),
+      IdKind::Ident | IdKind::Number => {
+        write!(f, "\"{}\"", self.value.replace('"', "\\\""))
+      }
+    }
+  }
+}
+
+impl<'src> From<&'src str> for Id<'src> {
+  fn from(value: &'src str) -> Self {
+    Self::new(value)
+  }
+}
+
+impl From<String> for Id<'_> {
+  fn from(value: String) -> Self {
+    Self::new(value)
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum IdKind {
+  HtmlString,
+  Ident,
+  Number,
+  String,
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct NodeId<'src> {
   pub id: Id<'src>,
   pub port: Option<Port<'src>>,
+  pub span: Range<usize>,
+}
+
+impl NodeId<'_> {
+  #[must_use]
+  pub fn into_owned(self) -> NodeId<'static> {
+    NodeId {
+      id: self.id.into_owned(),
+      port: self.port.map(Port::into_owned),
+      span: self.span,
+    }
+  }
+}
+
+impl Display for NodeId<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.id)?;
+
+    if let Some(port) = &self.port {
+      write!(f, "{port}")?;
+    }
+
+    Ok(())
+  }
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct NodeStatement<'src> {
   pub attributes: Vec<Attribute<'src>>,
   pub id: NodeId<'src>,
+  pub span: Range<usize>,
+}
+
+impl NodeStatement<'_> {
+  #[must_use]
+  pub fn into_owned(self) -> NodeStatement<'static> {
+    NodeStatement {
+      attributes: owned_attributes(self.attributes),
+      id: self.id.into_owned(),
+      span: self.span,
+    }
+  }
+}
+
+impl Display for NodeStatement<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.id)?;
+
+    if !self.attributes.is_empty() {
+      write!(f, " ")?;
+      write_attributes(f, &self.attributes)?;
+    }
+
+    Ok(())
+  }
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Port<'src> {
   pub compass: Option<Id<'src>>,
   pub id: Id<'src>,
+  pub span: Range<usize>,
+}
+
+impl Port<'_> {
+  #[must_use]
+  pub fn into_owned(self) -> Port<'static> {
+    Port {
+      compass: self.compass.map(Id::into_owned),
+      id: self.id.into_owned(),
+      span: self.span,
+    }
+  }
+}
+
+impl Display for Port<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, ":{}", self.id)?;
+
+    if let Some(compass) = &self.compass {
+      write!(f, ":{compass}")?;
+    }
+
+    Ok(())
+  }
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Statement<'src> {
   Assign(Id<'src>, Id<'src>),
   Attr(AttributeStatement<'src>),
@@ -85,8 +682,388 @@ pub enum Statement<'src> {
   Subgraph(Subgraph<'src>),
 }
 
+impl Statement<'_> {
+  #[must_use]
+  pub fn into_owned(self) -> Statement<'static> {
+    match self {
+      Statement::Assign(key, value) => {
+        Statement::Assign(key.into_owned(), value.into_owned())
+      }
+      Statement::Attr(statement) => Statement::Attr(statement.into_owned()),
+      Statement::Edge(statement) => Statement::Edge(statement.into_owned()),
+      Statement::Node(statement) => Statement::Node(statement.into_owned()),
+      Statement::Subgraph(subgraph) => {
+        Statement::Subgraph(subgraph.into_owned())
+      }
+    }
+  }
+
+  /// The byte range of this statement, excluding any trailing semicolon.
+  #[must_use]
+  pub fn span(&self) -> Range<usize> {
+    match self {
+      Statement::Assign(key, value) => key.span.start..value.span.end,
+      Statement::Attr(statement) => statement.span.clone(),
+      Statement::Edge(statement) => statement.span.clone(),
+      Statement::Node(statement) => statement.span.clone(),
+      Statement::Subgraph(subgraph) => subgraph.span.clone(),
+    }
+  }
+}
+
+impl Display for Statement<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write_statement(f, self, 0)
+  }
+}
+
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Subgraph<'src> {
   pub id: Option<Id<'src>>,
+  pub span: Range<usize>,
   pub statements: Vec<Statement<'src>>,
 }
+
+impl Subgraph<'_> {
+  #[must_use]
+  pub fn into_owned(self) -> Subgraph<'static> {
+    Subgraph {
+      id: self.id.map(Id::into_owned),
+      span: self.span,
+      statements: owned_statements(self.statements),
+    }
+  }
+}
+
+impl Display for Subgraph<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write_subgraph(f, self, 0)
+  }
+}
+
+fn owned_attributes(attributes: Vec<Attribute<'_>>) -> Vec<Attribute<'static>> {
+  attributes.into_iter().map(Attribute::into_owned).collect()
+}
+
+fn owned_statements(statements: Vec<Statement<'_>>) -> Vec<Statement<'static>> {
+  statements.into_iter().map(Statement::into_owned).collect()
+}
+
+fn write_attributes(
+  f: &mut Formatter<'_>,
+  attributes: &[Attribute<'_>],
+) -> fmt::Result {
+  write!(f, "[")?;
+
+  for (index, attribute) in attributes.iter().enumerate() {
+    if index > 0 {
+      write!(f, ", ")?;
+    }
+
+    write!(f, "{attribute}")?;
+  }
+
+  write!(f, "]")
+}
+
+fn write_block(
+  f: &mut Formatter<'_>,
+  statements: &[Statement<'_>],
+  depth: usize,
+) -> fmt::Result {
+  if statements.is_empty() {
+    return write!(f, "{{}}");
+  }
+
+  writeln!(f, "{{")?;
+
+  for statement in statements {
+    write!(f, "{:indent$}", "", indent = (depth + 1) * 2)?;
+    write_statement(f, statement, depth + 1)?;
+
+    if !matches!(statement, Statement::Subgraph(_)) {
+      write!(f, ";")?;
+    }
+
+    writeln!(f)?;
+  }
+
+  write!(f, "{:indent$}}}", "", indent = depth * 2)
+}
+
+fn write_inline_subgraph(
+  f: &mut Formatter<'_>,
+  subgraph: &Subgraph<'_>,
+) -> fmt::Result {
+  if let Some(id) = &subgraph.id {
+    write!(f, "subgraph {id} ")?;
+  }
+
+  if subgraph.statements.is_empty() {
+    return write!(f, "{{}}");
+  }
+
+  write!(f, "{{ ")?;
+
+  for (index, statement) in subgraph.statements.iter().enumerate() {
+    if index > 0 {
+      write!(f, "; ")?;
+    }
+
+    match statement {
+      Statement::Subgraph(subgraph) => write_inline_subgraph(f, subgraph)?,
+      statement => write_statement(f, statement, 0)?,
+    }
+  }
+
+  write!(f, " }}")
+}
+
+fn write_statement(
+  f: &mut Formatter<'_>,
+  statement: &Statement<'_>,
+  depth: usize,
+) -> fmt::Result {
+  match statement {
+    Statement::Assign(key, value) => write!(f, "{key}={value}"),
+    Statement::Attr(statement) => write!(f, "{statement}"),
+    Statement::Edge(statement) => write!(f, "{statement}"),
+    Statement::Node(statement) => write!(f, "{statement}"),
+    Statement::Subgraph(subgraph) => write_subgraph(f, subgraph, depth),
+  }
+}
+
+fn write_subgraph(
+  f: &mut Formatter<'_>,
+  subgraph: &Subgraph<'_>,
+  depth: usize,
+) -> fmt::Result {
+  write!(f, "subgraph ")?;
+
+  if let Some(id) = &subgraph.id {
+    write!(f, "{id} ")?;
+  }
+
+  write_block(f, &subgraph.statements, depth)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn attributes() {
+    case(
+      "digraph { node [shape=box; color=red] a [label=foo][style] }",
+      "digraph {\n  node [shape=box, color=red];\n  a [label=foo, style];\n}",
+    );
+  }
+
+  #[track_caller]
+  fn case(input: &str, expected: &str) {
+    let printed = parse(input).unwrap().to_string();
+
+    assert_eq!(printed, expected);
+
+    assert_eq!(
+      parse(&printed).unwrap().to_string(),
+      printed,
+      "printed graph did not round-trip",
+    );
+  }
+
+  #[test]
+  fn concatenation() {
+    case(
+      "digraph { a [label=\"foo\" +\n \"bar\"] }",
+      "digraph {\n  a [label=\"foo\" +\n \"bar\"];\n}",
+    );
+  }
+
+  #[test]
+  fn decoding() {
+    #[track_caller]
+    fn case(src: &str, expected: &str) {
+      let Statement::Assign(_, value) = &parse(src).unwrap().statements[0]
+      else {
+        panic!("expected assignment");
+      };
+
+      assert_eq!(value.decoded(), expected);
+    }
+
+    case("graph { a = foo }", "foo");
+    case("graph { a = <\\\"> }", "\\\"");
+    case(r#"graph { a = "foo\"bar" }"#, r#"foo"bar"#);
+    case(r#"graph { a = "foo\\bar\n" }"#, r"foo\\bar\n");
+    case("graph { a = \"foo\\\nbar\" }", "foobar");
+    case("graph { a = \"foo\\\r\nbar\" }", "foobar");
+    case(
+      r#"graph { a = "foo" + "bar" + "\"baz\"" }"#,
+      r#"foobar"baz""#,
+    );
+    case("graph { a = \"a + b\"\n  +\t\"c\" }", "a + bc");
+
+    assert!(matches!(
+      Id {
+        kind: IdKind::String,
+        span: 0..0,
+        value: Cow::Borrowed("plain"),
+      }
+      .decoded(),
+      Cow::Borrowed("plain"),
+    ));
+  }
+
+  #[test]
+  fn edges() {
+    case(
+      "digraph { a:p1:n -> b -> c [color=red] }",
+      "digraph {\n  a:p1:n -> b -> c [color=red];\n}",
+    );
+  }
+
+  #[test]
+  fn empty() {
+    case("digraph {}", "digraph {}");
+    case("strict graph G {}", "strict graph G {}");
+  }
+
+  #[test]
+  fn html_strings() {
+    case(
+      "digraph { a [label=<<b>bold</b>>] }",
+      "digraph {\n  a [label=<<b>bold</b>>];\n}",
+    );
+  }
+
+  #[test]
+  fn into_owned() {
+    let src = "strict digraph G { node [shape=box] a:p:n -> { b } [label=\"x\"]; c = d }";
+
+    let owned = {
+      let src = src.to_owned();
+      parse(&src).unwrap().into_owned()
+    };
+
+    assert_eq!(owned, parse(src).unwrap());
+
+    assert_matches!(
+      owned.id,
+      Some(Id {
+        value: Cow::Owned(ref value),
+        ..
+      }) if value == "G",
+    );
+  }
+
+  #[test]
+  fn quoting() {
+    #[track_caller]
+    fn case(kind: IdKind, value: &str, expected: &str) {
+      assert_eq!(
+        Id {
+          kind,
+          span: 0..0,
+          value: Cow::Borrowed(value),
+        }
+        .to_string(),
+        expected,
+      );
+    }
+
+    case(IdKind::Ident, "foo", "foo");
+    case(IdKind::Ident, "node", "\"node\"");
+    case(IdKind::Ident, "foo bar", "\"foo bar\"");
+    case(IdKind::Ident, "a\"b", r#""a\"b""#);
+    case(IdKind::Ident, "1a", "\"1a\"");
+    case(IdKind::Ident, "naïve", "naïve");
+    case(IdKind::Ident, "日本", "日本");
+    case(IdKind::Number, "-.5", "-.5");
+    case(IdKind::Number, "1.2.3", "\"1.2.3\"");
+    case(IdKind::String, r#"a\"b"#, r#""a\"b""#);
+    case(IdKind::HtmlString, "<b>x</b>", "<<b>x</b>>");
+  }
+
+  #[cfg(feature = "serde")]
+  #[test]
+  fn serde() {
+    let src = "strict digraph G { node [shape=box]; a:p:n -> { b c } -> d [label=<<b>x</b>>, w=1.5]; subgraph cluster_0 { \"e f\" } k = v }";
+
+    let graph = parse(src).unwrap();
+
+    let json = serde_json::to_string(&graph).unwrap();
+
+    assert_eq!(
+      serde_json::from_str::<Graph<'static>>(&json).unwrap(),
+      graph,
+    );
+
+    assert_eq!(
+      serde_json::to_value(parse("graph { a -- b }").unwrap()).unwrap(),
+      serde_json::json!({
+        "id": null,
+        "kind": "graph",
+        "span": { "start": 0, "end": 16 },
+        "statements": [{
+          "edge": {
+            "attributes": [],
+            "edges": [{
+              "operation": "dash_dash",
+              "span": { "start": 10, "end": 14 },
+              "target": {
+                "node_id": {
+                  "id": {
+                    "kind": "ident",
+                    "span": { "start": 13, "end": 14 },
+                    "value": "b",
+                  },
+                  "port": null,
+                  "span": { "start": 13, "end": 14 },
+                },
+              },
+            }],
+            "from": {
+              "node_id": {
+                "id": {
+                  "kind": "ident",
+                  "span": { "start": 8, "end": 9 },
+                  "value": "a",
+                },
+                "port": null,
+                "span": { "start": 8, "end": 9 },
+              },
+            },
+            "span": { "start": 8, "end": 14 },
+          },
+        }],
+        "strict": false,
+      }),
+    );
+  }
+
+  #[test]
+  fn statements() {
+    case(
+      "graph G { label = \"hi\"; graph [rankdir=LR] a -- b; c }",
+      "graph G {\n  label=\"hi\";\n  graph [rankdir=LR];\n  a -- b;\n  c;\n}",
+    );
+  }
+
+  #[test]
+  fn subgraph_edge_targets() {
+    case(
+      "digraph { a -> { b c } -> subgraph s { d { e } } }",
+      "digraph {\n  a -> { b; c } -> subgraph s { d; { e } };\n}",
+    );
+  }
+
+  #[test]
+  fn subgraphs() {
+    case(
+      "digraph { subgraph cluster_0 { a; subgraph { b } } { c } }",
+      "digraph {\n  subgraph cluster_0 {\n    a;\n    subgraph {\n      b;\n    }\n  }\n  subgraph {\n    c;\n  }\n}",
+    );
+  }
+}
@@ -0,0 +1,549 @@
+use super::*;
+
+/// An edge endpoint: a node, optionally with a port and a compass point.
+///
+/// Strings convert to endpoints without a port, so `builder.edge("a", "b")`
+/// connects two nodes, while `builder.edge(Endpoint::new("a").port("p1"), "b")`
+/// starts from port `p1` of `a`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Endpoint<'a> {
+  compass: Option<Id<'a>>,
+  id: Id<'a>,
+  port: Option<Id<'a>>,
+}
+
+impl<'a> Endpoint<'a> {
+  /// Attach at compass point `compass`, such as `n` or `se`, of the port, or
+  /// of the node if there is no port.
+  #[must_use]
+  pub fn compass(self, compass: impl Into<Id<'a>>) -> Self {
+    Self {
+      compass: Some(compass.into()),
+      ..self
+    }
+  }
+
+  pub fn new(id: impl Into<Id<'a>>) -> Self {
+    Self {
+      compass: None,
+      id: id.into(),
+      port: None,
+    }
+  }
+
+  fn node_id(self) -> NodeId<'a> {
+    let port = match (self.port, self.compass) {
+      (Some(id), compass) => Some(Port {
+        compass,
+        id,
+        span: 0..0,
+      }),
+      (None, Some(id)) => Some(Port {
+        compass: None,
+        id,
+        span: 0..0,
+      }),
+      (None, None) => None,
+    };
+
+    NodeId {
+      id: self.id,
+      port,
+      span: 0..0,
+    }
+  }
+
+  /// Attach at port `port` of the node, such as a record field.
+  #[must_use]
+  pub fn port(self, port: impl Into<Id<'a>>) -> Self {
+    Self {
+      port: Some(port.into()),
+      ..self
+    }
+  }
+}
+
+impl<'a> From<&'a str> for Endpoint<'a> {
+  fn from(id: &'a str) -> Self {
+    Self::new(id)
+  }
+}
+
+impl From<String> for Endpoint<'_> {
+  fn from(id: String) -> Self {
+    Self::new(id)
+  }
+}
+
+impl<'a> From<Id<'a>> for Endpoint<'a> {
+  fn from(id: Id<'a>) -> Self {
+    Self::new(id)
+  }
+}
+
+/// A builder for generating DOT from code.
+///
+/// Statements are added in order, and attributes set with
+/// [`attr`](GraphBuilder::attr) apply to the statement added last. Every
+/// identifier, attribute key and value is converted with [`Id::new`], so
+/// quoting and escaping is taken care of. Pass an [`Id::html`] for HTML-like
+/// labels.
+///
+/// The result is a [`Graph`] that prints as DOT, and can be resolved into a
+/// [`model::Graph`] like a parsed one. Its spans are all empty.
+///
+/// # Examples
+///
+/// ```
+/// use dotgraph::{Endpoint, GraphBuilder};
+///
+/// let graph = GraphBuilder::digraph("G")
+///   .node("a")
+///   .attr("shape", "box")
+///   .subgraph("cluster_0")
+///   .attr("label", "Back end")
+///   .node("db")
+///   .end()
+///   .edge(Endpoint::new("a").port("out"), "db")
+///   .attr("label", "reads from")
+///   .build();
+///
+/// assert_eq!(
+///   graph.to_string(),
+///   "digraph G {
+///   a [shape=box];
+///   subgraph cluster_0 {
+///     label=\"Back end\";
+///     db;
+///   }
+///   a:out -> db [label=\"reads from\"];
+/// }",
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct GraphBuilder<'a> {
+  graph: Graph<'a>,
+  subgraphs: Vec<Subgraph<'a>>,
+}
+
+impl<'a> GraphBuilder<'a> {
+  /// Set attribute `key` to `value` on the node, edge or attribute statement
+  /// added last. If the last statement is a subgraph, or there is none yet, a
+  /// `key=value` statement is added instead, setting an attribute of the graph
+  /// or subgraph being built.
+  #[must_use]
+  pub fn attr(
+    mut self,
+    key: impl Into<Id<'a>>,
+    value: impl Into<Id<'a>>,
+  ) -> Self {
+    let key = key.into();
+    let value = value.into();
+
+    let attributes = match self.statements().last_mut() {
+      Some(Statement::Attr(statement)) => &mut statement.attributes,
+      Some(Statement::Edge(statement)) => &mut statement.attributes,
+      Some(Statement::Node(statement)) => &mut statement.attributes,
+      Some(Statement::Assign(..) | Statement::Subgraph(_)) | None => {
+        return self.graph_attr(key, value);
+      }
+    };
+
+    attributes.push(Attribute {
+      key,
+      span: 0..0,
+      value: Some(value),
+    });
+
+    self
+  }
+
+  /// Finish building, closing any subgraphs that are still open.
+  #[must_use]
+  pub fn build(mut self) -> Graph<'a> {
+    while !self.subgraphs.is_empty() {
+      self = self.end();
+    }
+
+    self.graph
+  }
+
+  fn defaults(self, target: AttributeTarget) -> Self {
+    self.statement(Statement::Attr(AttributeStatement {
+      attributes: Vec::new(),
+      span: 0..0,
+      target,
+    }))
+  }
+
+  /// Start building a directed graph, named `id` unless it is `None`. Use
+  /// [`digraph_named`](GraphBuilder::digraph_named) for an owned name or an
+  /// [`Id`].
+  pub fn digraph(id: impl Into<Option<&'a str>>) -> Self {
+    Self::new(GraphKind::Digraph, id.into().map(Id::new))
+  }
+
+  /// Start building a directed graph named `id`.
+  pub fn digraph_named(id: impl Into<Id<'a>>) -> Self {
+    Self::new(GraphKind::Digraph, Some(id.into()))
+  }
+
+  /// Add an edge from `from` to `to`, using `->` in a directed graph and `--`
+  /// in an undirected one.
+  #[must_use]
+  pub fn edge(
+    self,
+    from: impl Into<Endpoint<'a>>,
+    to: impl Into<Endpoint<'a>>,
+  ) -> Self {
+    let operation = match self.graph.kind {
+      GraphKind::Digraph => EdgeOperation::Arrow,
+      GraphKind::Graph => EdgeOperation::DashDash,
+    };
+
+    self.statement(Statement::Edge(EdgeStatement {
+      attributes: Vec::new(),
+      edges: vec![Edge {
+        operation,
+        span: 0..0,
+        target: EdgeTarget::NodeId(to.into().node_id()),
+      }],
+      from: EdgeTarget::NodeId(from.into().node_id()),
+      span: 0..0,
+    }))
+  }
+
+  /// Add an `edge [...]` statement, whose attributes, set with
+  /// [`attr`](GraphBuilder::attr), apply to edges added after it.
+  #[must_use]
+  pub fn edge_defaults(self) -> Self {
+    self.defaults(AttributeTarget::Edge)
+  }
+
+  /// Close the subgraph opened last, continuing with the graph or subgraph
+  /// enclosing it. Does nothing if no subgraph is open.
+  #[must_use]
+  pub fn end(mut self) -> Self {
+    match self.subgraphs.pop() {
+      Some(subgraph) => self.statement(Statement::Subgraph(subgraph)),
+      None => self,
+    }
+  }
+
+  /// Start building an undirected graph, named `id` unless it is `None`. Use
+  /// [`graph_named`](GraphBuilder::graph_named) for an owned name or an
+  /// [`Id`].
+  pub fn graph(id: impl Into<Option<&'a str>>) -> Self {
+    Self::new(GraphKind::Graph, id.into().map(Id::new))
+  }
+
+  /// Add a `key=value` statement, setting an attribute of the graph or
+  /// subgraph being built.
+  #[must_use]
+  pub fn graph_attr(
+    self,
+    key: impl Into<Id<'a>>,
+    value: impl Into<Id<'a>>,
+  ) -> Self {
+    self.statement(Statement::Assign(key.into(), value.into()))
+  }
+
+  /// Start building an undirected graph named `id`.
+  pub fn graph_named(id: impl Into<Id<'a>>) -> Self {
+    Self::new(GraphKind::Graph, Some(id.into()))
+  }
+
+  /// Start building a graph of `kind`, named `id` unless it is `None`.
+  pub fn new(kind: GraphKind, id: Option<Id<'a>>) -> Self {
+    Self {
+      graph: Graph {
+        id,
+        kind,
+        span: 0..0,
+        statements: Vec::new(),
+        strict: false,
+      },
+      subgraphs: Vec::new(),
+    }
+  }
+
+  /// Add a node statement for `id`.
+  #[must_use]
+  pub fn node(self, id: impl Into<Id<'a>>) -> Self {
+    self.statement(Statement::Node(NodeStatement {
+      attributes: Vec::new(),
+      id: NodeId {
+        id: id.into(),
+        port: None,
+        span: 0..0,
+      },
+      span: 0..0,
+    }))
+  }
+
+  /// Add a `node [...]` statement, whose attributes, set with
+  /// [`attr`](GraphBuilder::attr), apply to nodes added after it.
+  #[must_use]
+  pub fn node_defaults(self) -> Self {
+    self.defaults(AttributeTarget::Node)
+  }
+
+  fn open(mut self, id: Option<Id<'a>>) -> Self {
+    self.subgraphs.push(Subgraph {
+      id,
+      span: 0..0,
+      statements: Vec::new(),
+    });
+
+    self
+  }
+
+  fn statement(mut self, statement: Statement<'a>) -> Self {
+    self.statements().push(statement);
+    self
+  }
+
+  fn statements(&mut self) -> &mut Vec<Statement<'a>> {
+    match self.subgraphs.last_mut() {
+      Some(subgraph) => &mut subgraph.statements,
+      None => &mut self.graph.statements,
+    }
+  }
+
+  /// Make the graph `strict`, so that Graphviz merges repeated edges.
+  #[must_use]
+  pub fn strict(mut self) -> Self {
+    self.graph.strict = true;
+    self
+  }
+
+  /// Open a subgraph, named `id` unless it is `None`, to which statements are
+  /// added until [`end`](GraphBuilder::end) is called. Subgraphs named with a
+  /// `cluster` prefix are drawn as clusters. Use
+  /// [`subgraph_named`](GraphBuilder::subgraph_named) for an owned name or an
+  /// [`Id`].
+  #[must_use]
+  pub fn subgraph(self, id: impl Into<Option<&'a str>>) -> Self {
+    self.open(id.into().map(Id::new))
+  }
+
+  /// Open a subgraph named `id`, like [`subgraph`](GraphBuilder::subgraph).
+  #[must_use]
+  pub fn subgraph_named(self, id: impl Into<Id<'a>>) -> Self {
+    self.open(Some(id.into()))
+  }
+}
+
+/// Builders print the graph they would build, closing any open subgraphs.
+impl Display for GraphBuilder<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.clone().build())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn attributes() {
+    assert_eq!(
+      GraphBuilder::digraph(None)
+        .attr("rankdir", "LR")
+        .node_defaults()
+        .attr("shape", "box")
+        .attr("style", "filled")
+        .node("a")
+        .attr("label", "A")
+        .edge_defaults()
+        .attr("color", "red")
+        .edge("a", "b")
+        .attr("weight", "2")
+        .graph_attr("label", "G")
+        .to_string(),
+      "digraph {
+  rankdir=LR;
+  node [shape=box, style=filled];
+  a [label=A];
+  edge [color=red];
+  a -> b [weight=2];
+  label=G;
+}",
+    );
+  }
+
+  #[test]
+  fn escaping() {
+    let values = [
+      "plain",
+      "two words",
+      "node",
+      "1.5",
+      "1.5.2",
+      "say \"hi\"",
+      "line\\nbreak",
+      "trailing\\",
+      "quote\\\"",
+      "",
+    ];
+
+    let mut builder = GraphBuilder::graph("names with spaces");
+
+    for value in values {
+      builder = builder.node(value).attr("label", value);
+    }
+
+    let printed = builder.to_string();
+
+    let graph = parse(&printed).unwrap();
+
+    let decoded = graph
+      .statements
+      .iter()
+      .map(|statement| {
+        let Statement::Node(statement) = statement else {
+          panic!("expected a node statement, found {statement}");
+        };
+
+        assert_eq!(
+          statement.attributes[0].value.as_ref().unwrap().decoded(),
+          statement.id.id.decoded(),
+        );
+
+        statement.id.id.decoded().into_owned()
+      })
+      .collect::<Vec<String>>();
+
+    assert_eq!(
+      decoded,
+      [
+        "plain",
+        "two words",
+        "node",
+        "1.5",
+        "1.5.2",
+        "say \"hi\"",
+        "line\\nbreak",
+        // DOT cannot write these backslashes on their own, so `Id::new`
+        // doubles them.
+        "trailing\\\\",
+        "quote\\\\\"",
+        "",
+      ],
+    );
+
+    assert_eq!(
+      graph.id.unwrap().decoded(),
+      "names with spaces",
+      "graph id was not quoted",
+    );
+  }
+
+  #[test]
+  fn html_labels() {
+    assert_eq!(
+      GraphBuilder::digraph(None)
+        .node("a")
+        .attr("label", Id::html("<b>bold</b>"))
+        .to_string(),
+      "digraph {\n  a [label=<<b>bold</b>>];\n}",
+    );
+  }
+
+  #[test]
+  fn model() {
+    let graph = GraphBuilder::graph(None)
+      .strict()
+      .node_defaults()
+      .attr("shape", "box")
+      .edge("a", "b")
+      .edge("b", "a")
+      .build();
+
+    let model = model::Graph::from(&graph);
+
+    assert!(model.strict);
+    assert_eq!(model.edges.len(), 1);
+    assert_eq!(model.nodes.len(), 2);
+    assert_eq!(model.nodes[0].attributes["shape"].value, "box");
+  }
+
+  #[test]
+  fn named() {
+    let mut builder = GraphBuilder::digraph_named(Id::new("my graph"));
+
+    for i in 0..2 {
+      builder = builder
+        .subgraph_named(format!("cluster_{i}"))
+        .node(format!("n{i}"))
+        .end();
+    }
+
+    assert_eq!(
+      builder.to_string(),
+      "digraph \"my graph\" {
+  subgraph cluster_0 {
+    n0;
+  }
+  subgraph cluster_1 {
+    n1;
+  }
+}",
+    );
+
+    assert_eq!(
+      GraphBuilder::graph_named(String::from("G")).to_string(),
+      "graph G {}",
+    );
+  }
+
+  #[test]
+  fn ports() {
+    assert_eq!(
+      GraphBuilder::digraph(None)
+        .edge(Endpoint::new("a").port("p1").compass("n"), "b")
+        .edge(
+          Endpoint::new("c").compass("se"),
+          Endpoint::new("d").port("x y")
+        )
+        .to_string(),
+      "digraph {\n  a:p1:n -> b;\n  c:se -> d:\"x y\";\n}",
+    );
+  }
+
+  #[test]
+  fn subgraphs() {
+    let builder = GraphBuilder::digraph("G")
+      .subgraph("cluster_0")
+      .attr("label", "outer")
+      .node("a")
+      .subgraph(None)
+      .attr("rank", "same")
+      .node("b")
+      .node("c")
+      .end()
+      .end()
+      .node("d")
+      .subgraph("cluster_1")
+      .node("e");
+
+    let expected = "digraph G {
+  subgraph cluster_0 {
+    label=outer;
+    a;
+    subgraph {
+      rank=same;
+      b;
+      c;
+    }
+  }
+  d;
+  subgraph cluster_1 {
+    e;
+  }
+}";
+
+    assert_eq!(builder.to_string(), expected);
+    assert_eq!(builder.build().to_string(), expected);
+  }
+}
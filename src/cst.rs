@@ -0,0 +1,807 @@
+//! A lossless concrete syntax tree.
+//!
+//! Unlike the [`Graph`] syntax tree, the concrete syntax tree keeps every
+//! token of the source, including whitespace and comments, so printing a tree
+//! gives back the exact source it was parsed from. Tools that rewrite DOT
+//! files can edit the tokens of a tree and print it, leaving everything
+//! outside the edited tokens byte-for-byte unchanged.
+//!
+//! A tree is made of [`Node`]s, each spanning a construct such as a graph,
+//! statement or attribute, whose children are the nodes and [`Token`]s it is
+//! made of, in source order. Whitespace and comments between two children
+//! belong to the node that contains both of them, so nodes start and end on
+//! the same tokens as the matching [`Graph`] syntax tree values, and
+//! [`Node::graph`] converts a graph node back into one.
+//!
+//! Token text is copy-on-write, so an edit can replace a token's text with a
+//! computed string, or insert a token made with [`Token::new`]. Converting an
+//! edited tree that no longer has the shape of a valid graph, such as one
+//! missing a node name, gives `None` rather than panicking.
+
+use super::*;
+
+/// Either a node or a token.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Element<'src> {
+  Node(Node<'src>),
+  Token(Token<'src>),
+}
+
+impl Element<'_> {
+  #[must_use]
+  pub fn span(&self) -> Range<usize> {
+    match self {
+      Element::Node(node) => node.span.clone(),
+      Element::Token(token) => token.span.clone(),
+    }
+  }
+}
+
+impl Display for Element<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self {
+      Element::Node(node) => write!(f, "{node}"),
+      Element::Token(token) => write!(f, "{}", token.text),
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Node<'src> {
+  pub children: Vec<Element<'src>>,
+  pub kind: NodeKind,
+  pub span: Range<usize>,
+}
+
+impl<'src> Node<'src> {
+  /// The attributes in this node's attribute lists.
+  fn attributes(&self) -> Option<Vec<Attribute<'src>>> {
+    self
+      .nodes(NodeKind::AttributeList)
+      .flat_map(|list| list.nodes(NodeKind::Attribute))
+      .map(|attribute| {
+        let mut ids = attribute.ids();
+
+        Some(Attribute {
+          key: ids.next()?,
+          span: attribute.span.clone(),
+          value: ids.next(),
+        })
+      })
+      .collect()
+  }
+
+  fn edge_target(&self) -> Option<EdgeTarget<'src>> {
+    Some(match self.kind {
+      NodeKind::NodeId => EdgeTarget::NodeId(self.node_id()?),
+      _ => EdgeTarget::Subgraph(self.subgraph()?),
+    })
+  }
+
+  /// Convert a [`NodeKind::Graph`] node into a [`Graph`], or return `None`
+  /// for any other kind of node, or for a graph that has been edited into
+  /// something that is not a valid graph.
+  ///
+  /// The graph is exactly what parsing the node's source would return, with
+  /// the same spans, as long as the tree has not been edited.
+  #[must_use]
+  pub fn graph(&self) -> Option<Graph<'src>> {
+    if self.kind != NodeKind::Graph {
+      return None;
+    }
+
+    let tokens = self.tokens();
+
+    Some(Graph {
+      id: self.ids().next(),
+      kind: if tokens.iter().any(|token| token.kind == TokenKind::Digraph) {
+        GraphKind::Digraph
+      } else {
+        GraphKind::Graph
+      },
+      span: self.span.clone(),
+      statements: self.statements()?,
+      strict: tokens.iter().any(|token| token.kind == TokenKind::Strict),
+    })
+  }
+
+  /// Convert every graph node among this node's children, such as the graphs
+  /// in a [`NodeKind::File`] node, leaving out any that do not convert.
+  #[must_use]
+  pub fn graphs(&self) -> Vec<Graph<'src>> {
+    self
+      .nodes(NodeKind::Graph)
+      .filter_map(Node::graph)
+      .collect()
+  }
+
+  /// The identifiers among this node's tokens.
+  fn ids(&self) -> impl Iterator<Item = Id<'src>> {
+    self.tokens().into_iter().filter_map(Token::id)
+  }
+
+  fn node_id(&self) -> Option<NodeId<'src>> {
+    let port = match self.nodes(NodeKind::Port).next() {
+      Some(port) => {
+        let mut ids = port.ids();
+        let id = ids.next()?;
+
+        Some(Port {
+          compass: ids.next(),
+          id,
+          span: port.span.clone(),
+        })
+      }
+      None => None,
+    };
+
+    Some(NodeId {
+      id: self.ids().next()?,
+      port,
+      span: self.span.clone(),
+    })
+  }
+
+  /// This node's child nodes of `kind`.
+  fn nodes(&self, kind: NodeKind) -> impl Iterator<Item = &Node<'src>> {
+    self.children.iter().filter_map(move |child| match child {
+      Element::Node(node) if node.kind == kind => Some(node),
+      _ => None,
+    })
+  }
+
+  fn statement(&self) -> Option<Statement<'src>> {
+    let targets = || {
+      self.children.iter().filter_map(|child| match child {
+        Element::Node(node)
+          if matches!(node.kind, NodeKind::NodeId | NodeKind::Subgraph) =>
+        {
+          Some(node)
+        }
+        _ => None,
+      })
+    };
+
+    Some(match self.kind {
+      NodeKind::Assign => {
+        let mut ids = self.ids();
+        Statement::Assign(ids.next()?, ids.next()?)
+      }
+      NodeKind::AttributeStatement => Statement::Attr(AttributeStatement {
+        attributes: self.attributes()?,
+        span: self.span.clone(),
+        target: match self.tokens().first().map(|token| token.kind) {
+          Some(TokenKind::Edge) => AttributeTarget::Edge,
+          Some(TokenKind::Node) => AttributeTarget::Node,
+          _ => AttributeTarget::Graph,
+        },
+      }),
+      NodeKind::EdgeStatement => Statement::Edge(EdgeStatement {
+        attributes: self.attributes()?,
+        edges: self
+          .nodes(NodeKind::Edge)
+          .map(|edge| {
+            Some(Edge {
+              operation: if edge
+                .tokens()
+                .iter()
+                .any(|token| token.kind == TokenKind::Arrow)
+              {
+                EdgeOperation::Arrow
+              } else {
+                EdgeOperation::DashDash
+              },
+              span: edge.span.clone(),
+              target: edge
+                .children
+                .iter()
+                .find_map(|child| match child {
+                  Element::Node(node) => Some(node),
+                  Element::Token(_) => None,
+                })?
+                .edge_target()?,
+            })
+          })
+          .collect::<Option<_>>()?,
+        from: targets().next()?.edge_target()?,
+        span: self.span.clone(),
+      }),
+      NodeKind::NodeStatement => Statement::Node(NodeStatement {
+        attributes: self.attributes()?,
+        id: targets().next()?.node_id()?,
+        span: self.span.clone(),
+      }),
+      NodeKind::Subgraph => Statement::Subgraph(self.subgraph()?),
+      _ => return None,
+    })
+  }
+
+  fn statements(&self) -> Option<Vec<Statement<'src>>> {
+    self
+      .children
+      .iter()
+      .filter_map(|child| match child {
+        Element::Node(node) if node.kind.is_statement() => {
+          Some(node.statement())
+        }
+        _ => None,
+      })
+      .collect()
+  }
+
+  fn subgraph(&self) -> Option<Subgraph<'src>> {
+    Some(Subgraph {
+      id: self.ids().next(),
+      span: self.span.clone(),
+      statements: self.statements()?,
+    })
+  }
+
+  /// This node's own tokens, leaving out whitespace, comments and the tokens
+  /// of child nodes.
+  fn tokens(&self) -> Vec<&Token<'src>> {
+    self
+      .children
+      .iter()
+      .filter_map(|child| match child {
+        Element::Token(token) if !token.kind.is_trivia() => Some(token),
+        _ => None,
+      })
+      .collect()
+  }
+}
+
+/// Nodes print the text of all of their tokens, which for an unedited tree is
+/// the source it was parsed from.
+impl Display for Node<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    for child in &self.children {
+      write!(f, "{child}")?;
+    }
+
+    Ok(())
+  }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum NodeKind {
+  /// An `id = id` statement.
+  Assign,
+  /// A single `id` or `id = id` in an attribute list.
+  Attribute,
+  /// A bracketed `[...]` attribute list.
+  AttributeList,
+  /// A `graph`, `node` or `edge` statement setting default attributes.
+  AttributeStatement,
+  /// An edge operator and the node or subgraph it leads to.
+  Edge,
+  EdgeStatement,
+  /// The root of a tree, holding every graph in the source.
+  File,
+  Graph,
+  /// A node name with an optional port.
+  NodeId,
+  NodeStatement,
+  /// A `:port` or `:port:compass` suffix of a node name.
+  Port,
+  Subgraph,
+}
+
+impl NodeKind {
+  fn is_statement(self) -> bool {
+    matches!(
+      self,
+      NodeKind::Assign
+        | NodeKind::AttributeStatement
+        | NodeKind::EdgeStatement
+        | NodeKind::NodeStatement
+        | NodeKind::Subgraph
+    )
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token<'src> {
+  pub kind: TokenKind,
+  pub span: Range<usize>,
+  /// The token's source text, including quotes and angle brackets.
+  pub text: Cow<'src, str>,
+}
+
+impl<'src> Token<'src> {
+  /// The identifier this token spells, without its quotes or angle brackets,
+  /// or `None` if it is not an identifier, or is missing them.
+  fn id(&self) -> Option<Id<'src>> {
+    fn value(kind: TokenKind, text: &str) -> Option<&str> {
+      match kind {
+        TokenKind::HtmlString => text.strip_prefix('<')?.strip_suffix('>'),
+        TokenKind::String => text.strip_prefix('"')?.strip_suffix('"'),
+        _ => Some(text),
+      }
+    }
+
+    let kind = match self.kind {
+      TokenKind::HtmlString => IdKind::HtmlString,
+      TokenKind::Ident => IdKind::Ident,
+      TokenKind::Number => IdKind::Number,
+      TokenKind::String => IdKind::String,
+      _ => return None,
+    };
+
+    Some(Id {
+      kind,
+      span: self.span.clone(),
+      value: match &self.text {
+        Cow::Borrowed(text) => Cow::Borrowed(value(self.kind, text)?),
+        Cow::Owned(text) => Cow::Owned(value(self.kind, text)?.to_owned()),
+      },
+    })
+  }
+
+  /// A token of `kind` with text `text`, for inserting into a tree. Its span
+  /// is empty, since it does not come from the source.
+  pub fn new(kind: TokenKind, text: impl Into<Cow<'src, str>>) -> Self {
+    Self {
+      kind,
+      span: 0..0,
+      text: text.into(),
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TokenKind {
+  Arrow,
+  CloseBrace,
+  CloseBracket,
+  Colon,
+  Comma,
+  /// A `//`, `/* */` or `#` comment.
+  Comment,
+  DashDash,
+  Digraph,
+  Edge,
+  Equals,
+  Graph,
+  HtmlString,
+  Ident,
+  Node,
+  Number,
+  OpenBrace,
+  OpenBracket,
+  Semicolon,
+  Strict,
+  String,
+  Subgraph,
+  Whitespace,
+}
+
+impl TokenKind {
+  /// Whether tokens of this kind are whitespace or comments, which have no
+  /// meaning in a graph.
+  #[must_use]
+  pub fn is_trivia(self) -> bool {
+    matches!(self, TokenKind::Comment | TokenKind::Whitespace)
+  }
+}
+
+/// Builds a tree by walking a parsed graph and consuming the source's tokens
+/// in order, giving each token to the innermost node whose span contains it.
+struct Builder<'src> {
+  next: usize,
+  stack: Vec<Node<'src>>,
+  tokens: Vec<Token<'src>>,
+}
+
+impl<'src> Builder<'src> {
+  fn attribute_lists(&mut self, attributes: &[Attribute<'src>], end: usize) {
+    let mut attributes = attributes.iter().peekable();
+
+    while let Some(start) = self.next_token(end, TokenKind::OpenBracket) {
+      let close = self
+        .next_token(end, TokenKind::CloseBracket)
+        .unwrap_or(end..end);
+
+      self.node(NodeKind::AttributeList, start.start..close.end, |builder| {
+        while let Some(attribute) =
+          attributes.next_if(|attribute| attribute.span.end <= close.start)
+        {
+          builder.node(NodeKind::Attribute, attribute.span.clone(), |_| {});
+        }
+      });
+    }
+  }
+
+  fn edge_target(&mut self, target: &EdgeTarget<'src>) {
+    match target {
+      EdgeTarget::NodeId(node_id) => self.node_id(node_id),
+      EdgeTarget::Subgraph(subgraph) => self.subgraph(subgraph),
+    }
+  }
+
+  fn graph(&mut self, graph: &Graph<'src>) {
+    self.node(NodeKind::Graph, graph.span.clone(), |builder| {
+      builder.statements(&graph.statements);
+    });
+  }
+
+  /// The span of the next token of `kind` that does not end after `end`,
+  /// without consuming any tokens.
+  fn next_token(
+    &mut self,
+    end: usize,
+    kind: TokenKind,
+  ) -> Option<Range<usize>> {
+    self.tokens[self.next..]
+      .iter()
+      .take_while(|token| token.span.end <= end)
+      .find(|token| token.kind == kind)
+      .map(|token| token.span.clone())
+  }
+
+  /// Add a node of `kind` spanning `span` to the current node, adding the
+  /// tokens before it to the current node, and building its children with
+  /// `children`.
+  fn node(
+    &mut self,
+    kind: NodeKind,
+    span: Range<usize>,
+    children: impl FnOnce(&mut Self),
+  ) {
+    self.tokens_until(span.start);
+
+    self.stack.push(Node {
+      children: Vec::new(),
+      kind,
+      span: span.clone(),
+    });
+
+    children(self);
+
+    self.tokens_until(span.end);
+
+    let node = self.stack.pop().unwrap();
+
+    self
+      .stack
+      .last_mut()
+      .unwrap()
+      .children
+      .push(Element::Node(node));
+  }
+
+  fn node_id(&mut self, node_id: &NodeId<'src>) {
+    self.node(NodeKind::NodeId, node_id.span.clone(), |builder| {
+      if let Some(port) = &node_id.port {
+        builder.node(NodeKind::Port, port.span.clone(), |_| {});
+      }
+    });
+  }
+
+  fn statements(&mut self, statements: &[Statement<'src>]) {
+    for statement in statements {
+      let span = statement.span();
+
+      match statement {
+        Statement::Assign(..) => self.node(NodeKind::Assign, span, |_| {}),
+        Statement::Attr(statement) => {
+          self.node(NodeKind::AttributeStatement, span, |builder| {
+            builder.attribute_lists(&statement.attributes, statement.span.end);
+          });
+        }
+        Statement::Edge(statement) => {
+          self.node(NodeKind::EdgeStatement, span, |builder| {
+            builder.edge_target(&statement.from);
+
+            for edge in &statement.edges {
+              builder.node(NodeKind::Edge, edge.span.clone(), |builder| {
+                builder.edge_target(&edge.target);
+              });
+            }
+
+            builder.attribute_lists(&statement.attributes, statement.span.end);
+          });
+        }
+        Statement::Node(statement) => {
+          self.node(NodeKind::NodeStatement, span, |builder| {
+            builder.node_id(&statement.id);
+            builder.attribute_lists(&statement.attributes, statement.span.end);
+          });
+        }
+        Statement::Subgraph(subgraph) => self.subgraph(subgraph),
+      }
+    }
+  }
+
+  fn subgraph(&mut self, subgraph: &Subgraph<'src>) {
+    self.node(NodeKind::Subgraph, subgraph.span.clone(), |builder| {
+      builder.statements(&subgraph.statements);
+    });
+  }
+
+  /// Add the tokens that end at or before `end` to the current node.
+  fn tokens_until(&mut self, end: usize) {
+    let node = self.stack.last_mut().unwrap();
+
+    while let Some(token) = self
+      .tokens
+      .get(self.next)
+      .filter(|token| token.span.end <= end)
+    {
+      node.children.push(Element::Token(token.clone()));
+      self.next += 1;
+    }
+  }
+}
+
+pub(crate) fn parse(src: &str) -> Result<Node<'_>, Vec<ParseError>> {
+  let mut graphs = Vec::new();
+  let mut errors = Vec::new();
+
+  for result in parser::parse_all(src) {
+    match result {
+      Ok(graph) => graphs.push(graph),
+      Err(mut graph_errors) => errors.append(&mut graph_errors),
+    }
+  }
+
+  if !errors.is_empty() {
+    return Err(errors);
+  }
+
+  let mut builder = Builder {
+    next: 0,
+    stack: vec![Node {
+      children: Vec::new(),
+      kind: NodeKind::File,
+      span: 0..src.len(),
+    }],
+    tokens: tokens(src),
+  };
+
+  for graph in &graphs {
+    builder.graph(graph);
+  }
+
+  builder.tokens_until(src.len());
+
+  Ok(builder.stack.pop().unwrap())
+}
+
+/// Every token in `src`, including comments, with the whitespace between
+/// tokens as tokens of its own.
+///
+/// `src` must have been lexed successfully before.
+fn tokens(src: &str) -> Vec<Token<'_>> {
+  let whitespace = |span: Range<usize>| {
+    (!span.is_empty()).then(|| Token {
+      kind: TokenKind::Whitespace,
+      span: span.clone(),
+      text: Cow::Borrowed(&src[span]),
+    })
+  };
+
+  let mut tokens = Vec::new();
+  let mut position = 0;
+
+  for (token, span) in lexer::lex(src).into_output().unwrap_or_default() {
+    tokens.extend(whitespace(position..span.start));
+
+    tokens.push(Token {
+      kind: match token {
+        token::Token::Arrow => TokenKind::Arrow,
+        token::Token::CloseBrace => TokenKind::CloseBrace,
+        token::Token::CloseBracket => TokenKind::CloseBracket,
+        token::Token::Colon => TokenKind::Colon,
+        token::Token::Comma => TokenKind::Comma,
+        token::Token::Comment(_) => TokenKind::Comment,
+        token::Token::DashDash => TokenKind::DashDash,
+        token::Token::Digraph => TokenKind::Digraph,
+        token::Token::Edge => TokenKind::Edge,
+        token::Token::Equals => TokenKind::Equals,
+        token::Token::Graph => TokenKind::Graph,
+        token::Token::HtmlString(_) => TokenKind::HtmlString,
+        token::Token::Ident(_) => TokenKind::Ident,
+        token::Token::Node => TokenKind::Node,
+        token::Token::Number(_) => TokenKind::Number,
+        token::Token::OpenBrace => TokenKind::OpenBrace,
+        token::Token::OpenBracket => TokenKind::OpenBracket,
+        token::Token::Semicolon => TokenKind::Semicolon,
+        token::Token::Strict => TokenKind::Strict,
+        token::Token::String(_) => TokenKind::String,
+        token::Token::Subgraph => TokenKind::Subgraph,
+      },
+      span: span.into_range(),
+      text: Cow::Borrowed(&src[span.into_range()]),
+    });
+
+    position = span.end;
+  }
+
+  tokens.extend(whitespace(position..src.len()));
+
+  tokens
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn edit() {
+    fn rename(node: &mut Node<'_>, name: &str) {
+      for child in &mut node.children {
+        match child {
+          Element::Node(node) => rename(node, name),
+          Element::Token(token) if token.text == "b" => {
+            *token = Token::new(TokenKind::String, format!("\"{name}\""));
+          }
+          Element::Token(_) => {}
+        }
+      }
+    }
+
+    let src = "digraph {\n  a -> b; // keep\n  b [color=red]\n}\n";
+
+    let mut tree = parse(src).unwrap();
+
+    rename(&mut tree, "c");
+
+    assert_eq!(
+      tree.to_string(),
+      "digraph {\n  a -> \"c\"; // keep\n  \"c\" [color=red]\n}\n",
+    );
+
+    assert_eq!(
+      tree.graphs()[0].to_string(),
+      "digraph {\n  a -> \"c\";\n  \"c\" [color=red];\n}",
+    );
+  }
+
+  #[test]
+  fn edit_into_invalid_graph() {
+    let mut tree = parse("graph { a -- b }").unwrap();
+
+    let Element::Node(graph) = &mut tree.children[0] else {
+      unreachable!();
+    };
+
+    assert!(graph.graph().is_some());
+
+    // Remove the node that `--` leads to.
+    let Element::Node(statement) = &mut graph.children[4] else {
+      unreachable!();
+    };
+
+    let Some(Element::Node(edge)) = statement.children.last_mut() else {
+      unreachable!();
+    };
+
+    edge.children.pop();
+
+    assert_eq!(graph.to_string(), "graph { a --  }");
+    assert_eq!(graph.graph(), None);
+
+    // A quoted string token without its closing quote.
+    let mut tree = parse("graph { \"a\" }").unwrap();
+
+    let Element::Node(graph) = &mut tree.children[0] else {
+      unreachable!();
+    };
+
+    let Element::Node(statement) = &mut graph.children[4] else {
+      unreachable!();
+    };
+
+    let Element::Node(node_id) = &mut statement.children[0] else {
+      unreachable!();
+    };
+
+    node_id.children[0] = Element::Token(Token::new(TokenKind::String, "\""));
+
+    assert_eq!(graph.graph(), None);
+    assert!(tree.graphs().is_empty());
+  }
+
+  #[test]
+  fn errors() {
+    assert_eq!(parse("graph { a -- } digraph { b }").unwrap_err().len(), 1);
+    assert!(parse("graph { \"a }").is_err());
+  }
+
+  #[test]
+  fn lossless() {
+    #[track_caller]
+    fn case(src: &str) {
+      let tree = parse(src).unwrap();
+
+      assert_eq!(tree.to_string(), src);
+
+      assert_eq!(
+        tree.graphs(),
+        parser::parse_all(src)
+          .into_iter()
+          .map(Result::unwrap)
+          .collect::<Vec<_>>(),
+      );
+    }
+
+    case("");
+    case("  // nothing\n");
+    case("digraph {}");
+    case("# 1 \"file.c\"\nstrict digraph G {\n  a -> b -> c;\n}\n");
+    case("graph{a--b[color=red,shape=box][style=filled];c}");
+    case(
+      "digraph { node [shape=box]; edge [color=\"re\" + \"d\"]; rankdir=LR }",
+    );
+    case(
+      "digraph {\n  a:p:n -> { b c } -> subgraph s { d } [label=<<b>x</b>>]\n}",
+    );
+    case(
+      "/* a */ graph /* b */ G /* c */ { /* d */ a /* e */ } /* f */ graph {}",
+    );
+    case(
+      "digraph {\n  subgraph cluster_0 {\n    label = \"x\";\n    a\n  }\n}\n",
+    );
+    case("graph { a [x] ; b [ y = 1 ; z ] }");
+  }
+
+  #[test]
+  fn structure() {
+    fn kinds(node: &Node<'_>, depth: usize, output: &mut Vec<String>) {
+      output.push(format!("{}{:?}", "  ".repeat(depth), node.kind));
+
+      for child in &node.children {
+        match child {
+          Element::Node(node) => kinds(node, depth + 1, output),
+          Element::Token(token) => output.push(format!(
+            "{}{:?} {:?}",
+            "  ".repeat(depth + 1),
+            token.kind,
+            token.text,
+          )),
+        }
+      }
+    }
+
+    let src = "graph { a -- b [color=red]; }";
+
+    let tree = parse(src).unwrap();
+
+    let mut output = Vec::new();
+
+    kinds(&tree, 0, &mut output);
+
+    assert_eq!(
+      output,
+      [
+        "File",
+        "  Graph",
+        "    Graph \"graph\"",
+        "    Whitespace \" \"",
+        "    OpenBrace \"{\"",
+        "    Whitespace \" \"",
+        "    EdgeStatement",
+        "      NodeId",
+        "        Ident \"a\"",
+        "      Whitespace \" \"",
+        "      Edge",
+        "        DashDash \"--\"",
+        "        Whitespace \" \"",
+        "        NodeId",
+        "          Ident \"b\"",
+        "      Whitespace \" \"",
+        "      AttributeList",
+        "        OpenBracket \"[\"",
+        "        Attribute",
+        "          Ident \"color\"",
+        "          Equals \"=\"",
+        "          Ident \"red\"",
+        "        CloseBracket \"]\"",
+        "    Semicolon \";\"",
+        "    Whitespace \" \"",
+        "    CloseBrace \"}\"",
+      ],
+    );
+  }
+}
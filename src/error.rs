@@ -0,0 +1,319 @@
+use {
+  super::*,
+  chumsky::{
+    error::{Error, LabelError, RichPattern, RichReason},
+    input::Input,
+    util::MaybeRef,
+  },
+};
+
+/// The kind of a [`ParseError`], so that errors can be told apart without
+/// comparing messages.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ErrorKind {
+  /// An attribute was set on an element it does not apply to, such as
+  /// `rankdir` on a node.
+  AttributeNotApplicable,
+  /// An attribute list followed a subgraph, which cannot have one.
+  AttributesAfterSubgraph,
+  /// An attribute was given a value its type does not accept.
+  InvalidAttributeValue,
+  /// An edge used `->` in an undirected graph, or `--` in a directed one.
+  InvalidEdgeOperation,
+  /// A Graphviz JSON document that is not valid JSON, or does not describe a
+  /// graph.
+  InvalidJson,
+  /// An identifier could not be parsed as the requested value type.
+  InvalidValue,
+  /// A `#` line did not start at the beginning of a line.
+  MisplacedPreprocessorLine,
+  /// The input ended inside a `{ ... }` block.
+  UnclosedBrace,
+  /// The input ended inside a `[ ... ]` attribute list.
+  UnclosedBracket,
+  /// The input ended inside a `/* ... */` comment.
+  UnclosedComment,
+  /// The input ended inside an HTML string.
+  UnclosedHtmlString,
+  /// The input ended inside a quoted string.
+  UnclosedString,
+  /// A character that does not start any token.
+  UnexpectedCharacter,
+  /// The input ended where more was expected.
+  UnexpectedEndOfInput,
+  /// A token that is not allowed where it appears.
+  UnexpectedToken,
+  /// An attribute name that Graphviz does not define.
+  UnknownAttribute,
+}
+
+impl ErrorKind {
+  /// A stable, kebab-case identifier for this kind of error, such as
+  /// `unclosed-brace`, for tools that consume diagnostics.
+  pub fn code(self) -> &'static str {
+    match self {
+      ErrorKind::AttributeNotApplicable => "attribute-not-applicable",
+      ErrorKind::AttributesAfterSubgraph => "attributes-after-subgraph",
+      ErrorKind::InvalidAttributeValue => "invalid-attribute-value",
+      ErrorKind::InvalidEdgeOperation => "invalid-edge-operation",
+      ErrorKind::InvalidJson => "invalid-json",
+      ErrorKind::InvalidValue => "invalid-value",
+      ErrorKind::MisplacedPreprocessorLine => "misplaced-preprocessor-line",
+      ErrorKind::UnclosedBrace => "unclosed-brace",
+      ErrorKind::UnclosedBracket => "unclosed-bracket",
+      ErrorKind::UnclosedComment => "unclosed-comment",
+      ErrorKind::UnclosedHtmlString => "unclosed-html-string",
+      ErrorKind::UnclosedString => "unclosed-string",
+      ErrorKind::UnexpectedCharacter => "unexpected-character",
+      ErrorKind::UnexpectedEndOfInput => "unexpected-end-of-input",
+      ErrorKind::UnexpectedToken => "unexpected-token",
+      ErrorKind::UnknownAttribute => "unknown-attribute",
+    }
+  }
+}
+
+/// A secondary location related to a [`ParseError`], such as the opening brace
+/// of a block that is never closed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Label {
+  pub message: String,
+  pub span: Range<usize>,
+}
+
+/// An error or warning found while lexing, parsing or validating DOT source.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+  /// A suggestion for fixing the error.
+  pub help: Option<String>,
+  pub kind: ErrorKind,
+  /// Other locations in the source that explain the error.
+  pub labels: Vec<Label>,
+  pub message: String,
+  /// Additional information about the error.
+  pub notes: Vec<String>,
+  pub severity: Severity,
+  pub span: Range<usize>,
+}
+
+impl ParseError {
+  /// Whether this is an error, rather than a warning.
+  #[must_use]
+  pub fn is_error(&self) -> bool {
+    self.severity == Severity::Error
+  }
+
+  pub(crate) fn new(
+    kind: ErrorKind,
+    message: impl Into<String>,
+    span: Range<usize>,
+  ) -> Self {
+    Self {
+      help: None,
+      kind,
+      labels: Vec::new(),
+      message: message.into(),
+      notes: Vec::new(),
+      severity: Severity::Error,
+      span,
+    }
+  }
+
+  pub(crate) fn warning(self) -> Self {
+    Self {
+      severity: Severity::Warning,
+      ..self
+    }
+  }
+
+  pub(crate) fn with_help(self, help: impl Into<String>) -> Self {
+    Self {
+      help: Some(help.into()),
+      ..self
+    }
+  }
+
+  pub(crate) fn with_label(
+    mut self,
+    message: impl Into<String>,
+    span: Range<usize>,
+  ) -> Self {
+    self.labels.push(Label {
+      message: message.into(),
+      span,
+    });
+    self
+  }
+
+  pub(crate) fn with_note(mut self, note: impl Into<String>) -> Self {
+    self.notes.push(note.into());
+    self
+  }
+}
+
+/// How serious a [`ParseError`] is.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Severity {
+  /// The source is not valid, and Graphviz would reject it.
+  Error,
+  /// The source is valid, but probably not what was meant. Graphviz accepts
+  /// it, and ignores what the warning is about.
+  Warning,
+}
+
+impl Display for Severity {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    f.write_str(match self {
+      Severity::Error => "error",
+      Severity::Warning => "warning",
+    })
+  }
+}
+
+/// The error type of the lexer and parser: a [`Rich`] error, together with
+/// the kind and help of custom errors, which `Rich` can only carry as a
+/// message.
+#[derive(Debug)]
+pub(crate) struct SyntaxError<'a, T> {
+  help: Option<String>,
+  kind: Option<ErrorKind>,
+  rich: Rich<'a, T, Span>,
+}
+
+impl<T> SyntaxError<'_, T> {
+  pub(crate) fn custom(
+    kind: ErrorKind,
+    span: Span,
+    message: impl ToString,
+  ) -> Self {
+    Self {
+      help: None,
+      kind: Some(kind),
+      rich: Rich::custom(span, message),
+    }
+  }
+
+  /// Whether the input ended where one of `expected` was expected.
+  pub(crate) fn expected_at_end(&self, expected: &T) -> bool
+  where
+    T: PartialEq,
+  {
+    match self.rich.reason() {
+      RichReason::ExpectedFound {
+        expected: patterns,
+        found: None,
+      } => patterns.iter().any(|pattern| {
+        matches!(pattern, RichPattern::Token(token) if **token == *expected)
+      }),
+      _ => false,
+    }
+  }
+
+  /// Convert into a [`ParseError`], using `unexpected` as the kind of errors
+  /// for unexpected input that did not reach the end of the input.
+  pub(crate) fn into_parse_error(self, unexpected: ErrorKind) -> ParseError
+  where
+    T: Display,
+  {
+    let kind = self.kind.unwrap_or(if self.rich.found().is_some() {
+      unexpected
+    } else {
+      ErrorKind::UnexpectedEndOfInput
+    });
+
+    let error = ParseError::new(
+      kind,
+      self.rich.to_string(),
+      self.rich.span().into_range(),
+    );
+
+    match self.help {
+      Some(help) => error.with_help(help),
+      None => error,
+    }
+  }
+
+  pub(crate) fn span(&self) -> Range<usize> {
+    self.rich.span().into_range()
+  }
+
+  pub(crate) fn with_help(self, help: impl Into<String>) -> Self {
+    Self {
+      help: Some(help.into()),
+      ..self
+    }
+  }
+}
+
+impl<'a, I> Error<'a, I> for SyntaxError<'a, I::Token>
+where
+  I: Input<'a, Span = Span>,
+  I::Token: PartialEq,
+{
+  fn merge(self, other: Self) -> Self {
+    Self {
+      help: self.help.or(other.help),
+      kind: self.kind.or(other.kind),
+      rich: Error::<I>::merge(self.rich, other.rich),
+    }
+  }
+}
+
+impl<'a, I, L> LabelError<'a, I, L> for SyntaxError<'a, I::Token>
+where
+  I: Input<'a, Span = Span>,
+  I::Token: PartialEq,
+  Rich<'a, I::Token, Span>: LabelError<'a, I, L>,
+{
+  fn expected_found<E: IntoIterator<Item = L>>(
+    expected: E,
+    found: Option<MaybeRef<'a, I::Token>>,
+    span: Span,
+  ) -> Self {
+    Self {
+      help: None,
+      kind: None,
+      rich: LabelError::<I, L>::expected_found(expected, found, span),
+    }
+  }
+
+  fn in_context(&mut self, label: L, span: Span) {
+    LabelError::<I, L>::in_context(&mut self.rich, label, span);
+  }
+
+  fn label_with(&mut self, label: L) {
+    LabelError::<I, L>::label_with(&mut self.rich, label);
+  }
+
+  fn merge_expected_found<E: IntoIterator<Item = L>>(
+    self,
+    expected: E,
+    found: Option<MaybeRef<'a, I::Token>>,
+    span: Span,
+  ) -> Self
+  where
+    Self: Error<'a, I>,
+  {
+    Self {
+      help: self.help,
+      kind: self.kind,
+      rich: LabelError::<I, L>::merge_expected_found(
+        self.rich, expected, found, span,
+      ),
+    }
+  }
+
+  fn replace_expected_found<E: IntoIterator<Item = L>>(
+    self,
+    expected: E,
+    found: Option<MaybeRef<'a, I::Token>>,
+    span: Span,
+  ) -> Self {
+    Self {
+      help: None,
+      kind: None,
+      rich: LabelError::<I, L>::replace_expected_found(
+        self.rich, expected, found, span,
+      ),
+    }
+  }
+}
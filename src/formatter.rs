@@ -0,0 +1,339 @@
+use {super::*, std::fmt::Write};
+
+/// Writes a graph in the same layout as its `Display` implementation, while
+/// weaving the comments found in the original source back in.
+///
+/// Comments keep their position relative to statements: comments on their
+/// own line stay above the statement that follows them, and comments that
+/// trail a statement on the same line, or that sit inside a statement, are
+/// written after that statement. A single blank line between statements is
+/// preserved, runs of blank lines are collapsed. Lines starting with `#` are
+/// only comments at the start of a line, so they are never indented.
+struct Printer<'src> {
+  comments: Vec<(&'src str, Range<usize>)>,
+  first: bool,
+  next_comment: usize,
+  output: String,
+  position: usize,
+  src: &'src str,
+}
+
+impl<'src> Printer<'src> {
+  fn block(
+    &mut self,
+    statements: &[Statement<'src>],
+    depth: usize,
+    end: usize,
+  ) {
+    if statements.is_empty()
+      && self.peek().is_none_or(|(_, span)| span.start >= end)
+    {
+      self.output.push_str("{}");
+      return;
+    }
+
+    self.output.push_str("{\n");
+
+    self.first = true;
+
+    for (index, statement) in statements.iter().enumerate() {
+      let span = statement.span();
+
+      self.leading_comments(span.start, depth + 1);
+
+      self.separate(span.start);
+
+      self.indent(depth + 1);
+
+      match statement {
+        Statement::Subgraph(subgraph) => self.subgraph(subgraph, depth + 1),
+        statement => write!(self.output, "{statement};").unwrap(),
+      }
+
+      self.position = span.end;
+
+      let limit = statements
+        .get(index + 1)
+        .map_or(end, |next| next.span().start);
+
+      self.trailing_comments(limit, depth + 1);
+
+      self.output.push('\n');
+
+      self.first = false;
+    }
+
+    self.leading_comments(end, depth + 1);
+
+    self.indent(depth);
+
+    self.output.push('}');
+
+    self.first = false;
+  }
+
+  fn comment(&mut self) {
+    let (text, span) = self.comments[self.next_comment].clone();
+
+    self.output.push_str(text);
+    self.position = self.position.max(span.end);
+    self.next_comment += 1;
+  }
+
+  /// Write `graph`, followed by any comments on the same line before `end`.
+  fn graph(&mut self, graph: &Graph<'src>, end: usize) {
+    self.leading_comments(graph.span.start, 0);
+
+    self.separate(graph.span.start);
+
+    if graph.strict {
+      self.output.push_str("strict ");
+    }
+
+    write!(self.output, "{}", graph.kind).unwrap();
+
+    if let Some(id) = &graph.id {
+      write!(self.output, " {id}").unwrap();
+    }
+
+    self.output.push(' ');
+
+    self.block(&graph.statements, 0, graph.span.end - 1);
+
+    self.position = graph.span.end;
+
+    self.trailing_comments(end, 0);
+
+    self.output.push('\n');
+  }
+
+  fn indent(&mut self, depth: usize) {
+    write!(self.output, "{:indent$}", "", indent = depth * 2).unwrap();
+  }
+
+  /// Write every comment that starts before `limit` on its own line.
+  fn leading_comments(&mut self, limit: usize, depth: usize) {
+    while let Some((_, span)) = self.peek() {
+      if span.start >= limit {
+        break;
+      }
+
+      let start = span.start;
+
+      self.separate(start);
+
+      if !self.comments[self.next_comment].0.starts_with('#') {
+        self.indent(depth);
+      }
+
+      self.comment();
+      self.output.push('\n');
+
+      self.first = false;
+    }
+  }
+
+  fn peek(&self) -> Option<&(&'src str, Range<usize>)> {
+    self.comments.get(self.next_comment)
+  }
+
+  /// Insert a blank line if the source had one between the previous item and
+  /// the item starting at `start`.
+  fn separate(&mut self, start: usize) {
+    if !self.first
+      && self.src[self.position..start]
+        .chars()
+        .filter(|&c| c == '\n')
+        .count()
+        >= 2
+    {
+      self.output.push('\n');
+    }
+  }
+
+  fn subgraph(&mut self, subgraph: &Subgraph<'src>, depth: usize) {
+    self.output.push_str("subgraph ");
+
+    if let Some(id) = &subgraph.id {
+      write!(self.output, "{id} ").unwrap();
+    }
+
+    self.block(&subgraph.statements, depth, subgraph.span.end - 1);
+  }
+
+  /// Write comments inside the item that ended at `self.position`, and any
+  /// comment that follows it on the same line before `limit`.
+  fn trailing_comments(&mut self, limit: usize, depth: usize) {
+    let mut first = true;
+
+    while let Some((_, span)) = self.peek() {
+      let inner = span.start < self.position;
+
+      let same_line = span.start < limit
+        && !inner
+        && !self.src[self.position..span.start].contains('\n');
+
+      if !inner && !same_line {
+        break;
+      }
+
+      if self.comments[self.next_comment].0.starts_with('#') {
+        self.output.push('\n');
+      } else if first {
+        self.output.push(' ');
+      } else {
+        self.output.push('\n');
+        self.indent(depth);
+      }
+
+      self.comment();
+
+      first = false;
+    }
+  }
+}
+
+pub(crate) fn format(src: &str) -> Result<String, Vec<ParseError>> {
+  let mut graphs = Vec::new();
+  let mut errors = Vec::new();
+
+  for result in parser::parse_all(src) {
+    match result {
+      Ok(graph) => graphs.push(graph),
+      Err(graph_errors) => errors.extend(graph_errors),
+    }
+  }
+
+  if !errors.is_empty() {
+    return Err(errors);
+  }
+
+  let comments = lexer::lex(src)
+    .into_output()
+    .unwrap_or_default()
+    .into_iter()
+    .filter_map(|(token, span)| match token {
+      Token::Comment(text) => Some((text, span.into_range())),
+      _ => None,
+    })
+    .collect();
+
+  let mut printer = Printer {
+    comments,
+    first: true,
+    next_comment: 0,
+    output: String::new(),
+    position: 0,
+    src,
+  };
+
+  for (index, graph) in graphs.iter().enumerate() {
+    let end = graphs
+      .get(index + 1)
+      .map_or(src.len(), |next| next.span.start);
+
+    printer.graph(graph, end);
+  }
+
+  printer.leading_comments(src.len(), 0);
+
+  Ok(printer.output)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn blank_lines() {
+    case(
+      "digraph {\n  a\n\n\n\n  b\n  c\n}",
+      "digraph {\n  a;\n\n  b;\n  c;\n}\n",
+    );
+  }
+
+  #[track_caller]
+  fn case(input: &str, expected: &str) {
+    let formatted = format(input).unwrap();
+
+    assert_eq!(formatted, expected);
+
+    assert_eq!(
+      format(&formatted).unwrap(),
+      formatted,
+      "formatting is not idempotent",
+    );
+  }
+
+  #[test]
+  fn comments_before_and_after_graph() {
+    case(
+      "// header\n\n/* block */\ndigraph {} // trailing\n// footer\n",
+      "// header\n\n/* block */\ndigraph {} // trailing\n// footer\n",
+    );
+  }
+
+  #[test]
+  fn comments_between_statements() {
+    case(
+      "digraph {\n  // leading\n  a -> b // trailing\n  /* own line */\n  c; d /* same line */\n}",
+      "digraph {\n  // leading\n  a -> b; // trailing\n  /* own line */\n  c;\n  d; /* same line */\n}\n",
+    );
+  }
+
+  #[test]
+  fn comments_in_empty_block() {
+    case(
+      "digraph { /* nothing */ }",
+      "digraph {\n  /* nothing */\n}\n",
+    );
+  }
+
+  #[test]
+  fn comments_in_subgraphs() {
+    case(
+      "digraph {\nsubgraph cluster_0 {\n// first\na\n// last\n}\n}",
+      "digraph {\n  subgraph cluster_0 {\n    // first\n    a;\n    // last\n  }\n}\n",
+    );
+  }
+
+  #[test]
+  fn comments_inside_statements() {
+    case(
+      "digraph {\n  a [\n    color=red // the color\n    shape=box\n  ]\n}",
+      "digraph {\n  a [color=red, shape=box]; // the color\n}\n",
+    );
+  }
+
+  #[test]
+  fn comments_preprocessor() {
+    case(
+      "# 1 \"a.c\"\ndigraph {\n# 2 \"a.c\"\n  subgraph s {\n#x\n  a [\n# 3\n  color=red] // c\n  }\n}",
+      "# 1 \"a.c\"\ndigraph {\n# 2 \"a.c\"\n  subgraph s {\n#x\n    a [color=red];\n# 3\n    // c\n  }\n}\n",
+    );
+  }
+
+  #[test]
+  fn indentation() {
+    case(
+      "digraph G {\n\ta\n        subgraph s {\n b\n          }\n}",
+      "digraph G {\n  a;\n  subgraph s {\n    b;\n  }\n}\n",
+    );
+  }
+
+  #[test]
+  fn multiple_graphs() {
+    case(
+      "graph{a} // first\n\n\n// second\ndigraph{b}digraph{c}",
+      "graph {\n  a;\n} // first\n\n// second\ndigraph {\n  b;\n}\ndigraph {\n  c;\n}\n",
+    );
+  }
+
+  #[test]
+  fn punctuation() {
+    case(
+      "graph { a [x=1; y=2,z=3] [w=4]; b -- c }",
+      "graph {\n  a [x=1, y=2, z=3, w=4];\n  b -- c;\n}\n",
+    );
+  }
+}
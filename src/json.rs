@@ -0,0 +1,820 @@
+use {
+  super::*,
+  model::Attributes,
+  serde_json::{Map, Value},
+  std::collections::{BTreeSet, HashMap},
+};
+
+/// Keys of Graphviz JSON objects that describe the structure of the graph,
+/// rather than attributes.
+const STRUCTURE: [&str; 11] = [
+  "_gvid",
+  "_subgraph_cnt",
+  "directed",
+  "edges",
+  "head",
+  "name",
+  "nodes",
+  "objects",
+  "strict",
+  "subgraphs",
+  "tail",
+];
+
+type Object = Map<String, Value>;
+
+struct Reader<'a> {
+  directed: bool,
+  edges: Vec<(u64, &'a Object)>,
+  nodes: Vec<(u64, &'a Object)>,
+  subgraphs: Vec<(u64, &'a Object)>,
+}
+
+impl Reader<'_> {
+  fn edge(&self, gvid: u64) -> Result<EdgeStatement<'static>, ParseError> {
+    let edge = lookup(&self.edges, "edge", gvid)?;
+
+    let endpoint = |key: &str, port_key: &str| {
+      let node = edge
+        .get(key)
+        .and_then(Value::as_u64)
+        .ok_or_else(|| invalid(format!("edge {gvid} has no `{key}` node")))?;
+
+      Ok::<_, ParseError>(NodeId {
+        id: Id::new(name(lookup(&self.nodes, "node", node)?, "node", node)?),
+        port: edge.get(port_key).and_then(Value::as_str).map(port_id),
+        span: 0..0,
+      })
+    };
+
+    Ok(EdgeStatement {
+      attributes: attributes(edge, &["headport", "tailport"]),
+      edges: vec![Edge {
+        operation: if self.directed {
+          EdgeOperation::Arrow
+        } else {
+          EdgeOperation::DashDash
+        },
+        span: 0..0,
+        target: EdgeTarget::NodeId(endpoint("head", "headport")?),
+      }],
+      from: EdgeTarget::NodeId(endpoint("tail", "tailport")?),
+      span: 0..0,
+    })
+  }
+
+  /// The `_gvid`s of the nodes or edges, as given by `key`, in subgraph
+  /// `gvid` and the subgraphs nested in it.
+  fn members(&self, gvid: u64, key: &str) -> Result<BTreeSet<u64>, ParseError> {
+    let subgraph = lookup(&self.subgraphs, "subgraph", gvid)?;
+
+    let mut members =
+      gvids(subgraph, key)?.into_iter().collect::<BTreeSet<u64>>();
+
+    for child in gvids(subgraph, "subgraphs")? {
+      members.extend(self.members(child, key)?);
+    }
+
+    Ok(members)
+  }
+
+  /// The statements of a graph or subgraph: its attributes, the nodes and
+  /// edges in it that are not in one of its subgraphs, and its subgraphs.
+  fn statements(
+    &self,
+    object: &Object,
+    children: &[u64],
+    nodes: &[u64],
+    edges: &[u64],
+  ) -> Result<Vec<Statement<'static>>, ParseError> {
+    let mut statements = attributes(object, &[])
+      .into_iter()
+      .map(|attribute| {
+        Statement::Assign(
+          attribute.key,
+          attribute.value.unwrap_or_else(|| Id::new("true")),
+        )
+      })
+      .collect::<Vec<Statement>>();
+
+    let mut nested = (BTreeSet::new(), BTreeSet::new());
+
+    let mut subgraphs = Vec::new();
+
+    for &child in children {
+      let edges = self.members(child, "edges")?;
+
+      nested.0.extend(self.members(child, "nodes")?);
+      nested.1.extend(edges.iter().copied());
+
+      subgraphs.push((edges.first().copied(), child));
+    }
+
+    for &node in nodes.iter().filter(|node| !nested.0.contains(node)) {
+      statements.push(Statement::Node(NodeStatement {
+        attributes: Vec::new(),
+        id: NodeId {
+          id: Id::new(name(lookup(&self.nodes, "node", node)?, "node", node)?),
+          port: None,
+          span: 0..0,
+        },
+        span: 0..0,
+      }));
+    }
+
+    // Edges are numbered in the order they were declared, so each edge goes
+    // before the first subgraph holding a later one, keeping that order.
+    let mut edges = edges
+      .iter()
+      .copied()
+      .filter(|edge| !nested.1.contains(edge))
+      .peekable();
+
+    for (first, child) in subgraphs {
+      while let Some(edge) =
+        edges.next_if(|&edge| first.is_some_and(|first| edge < first))
+      {
+        statements.push(Statement::Edge(self.edge(edge)?));
+      }
+
+      statements.push(Statement::Subgraph(self.subgraph(child)?));
+    }
+
+    for edge in edges {
+      statements.push(Statement::Edge(self.edge(edge)?));
+    }
+
+    Ok(statements)
+  }
+
+  fn subgraph(&self, gvid: u64) -> Result<Subgraph<'static>, ParseError> {
+    let subgraph = lookup(&self.subgraphs, "subgraph", gvid)?;
+
+    Ok(Subgraph {
+      id: id(subgraph),
+      span: 0..0,
+      statements: self.statements(
+        subgraph,
+        &gvids(subgraph, "subgraphs")?,
+        &gvids(subgraph, "nodes")?,
+        &gvids(subgraph, "edges")?,
+      )?,
+    })
+  }
+}
+
+struct Writer {
+  anonymous: usize,
+  subgraphs: Vec<Value>,
+}
+
+impl Writer {
+  fn name(&mut self, id: Option<&Id<'_>>) -> String {
+    if let Some(id) = id {
+      return id.decoded().into_owned();
+    }
+
+    self.anonymous += 1;
+
+    format!("%{}", self.anonymous)
+  }
+
+  /// Add `subgraphs` and the subgraphs nested in them to the list of
+  /// subgraphs, in pre-order, returning the `_gvid`s of `subgraphs`.
+  fn subgraphs(&mut self, subgraphs: &[model::Subgraph<'_>]) -> Vec<usize> {
+    subgraphs
+      .iter()
+      .map(|subgraph| {
+        let gvid = self.subgraphs.len();
+
+        let mut object = Map::new();
+
+        object.insert("name".into(), self.name(subgraph.id.as_ref()).into());
+
+        insert_attributes(&mut object, &subgraph.attributes);
+
+        object.insert("_gvid".into(), gvid.into());
+
+        self.subgraphs.push(Value::Null);
+
+        let children = self.subgraphs(&subgraph.subgraphs);
+
+        // Like Graphviz, list members in `_gvid` order rather than the
+        // order they were added to the subgraph in.
+        for (key, mut ids) in [
+          ("subgraphs", children),
+          ("nodes", subgraph.nodes.clone()),
+          ("edges", subgraph.edges.clone()),
+        ] {
+          ids.sort_unstable();
+
+          if !ids.is_empty() {
+            object.insert(key.into(), ids.into());
+          }
+        }
+
+        self.subgraphs[gvid] = object.into();
+
+        gvid
+      })
+      .collect()
+  }
+}
+
+/// The attributes of a Graphviz JSON object, leaving out `skip` and keys
+/// that describe the graph's structure. Values that are not strings, numbers
+/// or booleans, such as the drawing operations of `-Tjson` output, are left
+/// out too.
+fn attributes(object: &Object, skip: &[&str]) -> Vec<Attribute<'static>> {
+  object
+    .iter()
+    .filter(|(key, _)| {
+      !STRUCTURE.contains(&key.as_str()) && !skip.contains(&key.as_str())
+    })
+    .filter_map(|(key, value)| {
+      let value = match value {
+        Value::Bool(value) => value.to_string(),
+        Value::Number(value) => value.to_string(),
+        Value::String(value) => value.clone(),
+        Value::Array(_) | Value::Null | Value::Object(_) => return None,
+      };
+
+      Some(Attribute {
+        key: Id::new(key.clone()),
+        span: 0..0,
+        value: Some(Id::new(value)),
+      })
+    })
+    .collect()
+}
+
+/// The `_gvid`s listed under `key` in `object`.
+fn gvids(object: &Object, key: &str) -> Result<Vec<u64>, ParseError> {
+  let Some(value) = object.get(key) else {
+    return Ok(Vec::new());
+  };
+
+  value
+    .as_array()
+    .and_then(|gvids| gvids.iter().map(Value::as_u64).collect())
+    .ok_or_else(|| invalid(format!("`{key}` must be an array of `_gvid`s")))
+}
+
+/// The identifier for the name of a graph or subgraph. Graphviz names
+/// anonymous graphs with a `%` prefix, so those are left anonymous.
+fn id(object: &Object) -> Option<Id<'static>> {
+  object
+    .get("name")
+    .and_then(Value::as_str)
+    .filter(|name| !name.starts_with('%'))
+    .map(|name| Id::new(name.to_owned()))
+}
+
+/// Objects of one kind, sorted by `_gvid`, which defaults to their position.
+fn index<'a>(
+  objects: &'a [Value],
+  kind: &str,
+) -> Result<Vec<(u64, &'a Object)>, ParseError> {
+  let mut index = objects
+    .iter()
+    .zip(0..)
+    .map(|(object, position)| {
+      let object = object.as_object().ok_or_else(|| {
+        invalid(format!("{kind} {position} is not an object"))
+      })?;
+
+      Ok((
+        object
+          .get("_gvid")
+          .and_then(Value::as_u64)
+          .unwrap_or(position),
+        object,
+      ))
+    })
+    .collect::<Result<Vec<(u64, &Object)>, ParseError>>()?;
+
+  index.sort_by_key(|(gvid, _)| *gvid);
+
+  Ok(index)
+}
+
+fn insert_attributes(object: &mut Object, attributes: &Attributes<'_>) {
+  for (key, value) in attributes {
+    object.insert(key.clone(), value.decoded().into());
+  }
+}
+
+fn invalid(message: impl Into<String>) -> ParseError {
+  ParseError::new(ErrorKind::InvalidJson, message, 0..0)
+}
+
+fn lookup<'a>(
+  objects: &[(u64, &'a Object)],
+  kind: &str,
+  gvid: u64,
+) -> Result<&'a Object, ParseError> {
+  objects
+    .binary_search_by_key(&gvid, |(gvid, _)| *gvid)
+    .map(|index| objects[index].1)
+    .map_err(|_| invalid(format!("there is no {kind} with `_gvid` {gvid}")))
+}
+
+fn name(object: &Object, kind: &str, gvid: u64) -> Result<String, ParseError> {
+  object
+    .get("name")
+    .and_then(Value::as_str)
+    .map(str::to_owned)
+    .ok_or_else(|| invalid(format!("{kind} {gvid} has no `name`")))
+}
+
+/// The value of a `tailport` or `headport` attribute, such as `p:n`.
+fn port(port: &Port<'_>) -> String {
+  match &port.compass {
+    Some(compass) => format!("{}:{}", port.id.decoded(), compass.decoded()),
+    None => port.id.decoded().into_owned(),
+  }
+}
+
+/// The port for a `tailport` or `headport` attribute value.
+fn port_id(port: &str) -> Port<'static> {
+  let (id, compass) = match port.split_once(':') {
+    Some((id, compass)) => (id, Some(compass)),
+    None => (port, None),
+  };
+
+  Port {
+    compass: compass.map(|compass| Id::new(compass.to_owned())),
+    id: Id::new(id.to_owned()),
+    span: 0..0,
+  }
+}
+
+pub(crate) fn read(src: &str) -> Result<Graph<'static>, ParseError> {
+  let document = serde_json::from_str::<Value>(src).map_err(|error| {
+    let offset = src
+      .split_inclusive('\n')
+      .take(error.line().saturating_sub(1))
+      .map(str::len)
+      .sum::<usize>()
+      + error.column().saturating_sub(1);
+
+    let offset = offset.min(src.len());
+
+    ParseError::new(ErrorKind::InvalidJson, error.to_string(), offset..offset)
+  })?;
+
+  let graph = document
+    .as_object()
+    .ok_or_else(|| invalid("a Graphviz JSON document must be an object"))?;
+
+  let objects = match graph.get("objects") {
+    Some(objects) => objects
+      .as_array()
+      .ok_or_else(|| invalid("`objects` must be an array"))?
+      .as_slice(),
+    None => &[],
+  };
+
+  let count = graph
+    .get("_subgraph_cnt")
+    .and_then(Value::as_u64)
+    .unwrap_or(0);
+
+  let count = usize::try_from(count)
+    .ok()
+    .filter(|count| *count <= objects.len())
+    .ok_or_else(|| {
+      invalid(format!(
+        "`_subgraph_cnt` is {count}, but there are only {} objects",
+        objects.len(),
+      ))
+    })?;
+
+  let (subgraphs, nodes) = objects.split_at(count);
+
+  let edges = match graph.get("edges") {
+    Some(edges) => edges
+      .as_array()
+      .ok_or_else(|| invalid("`edges` must be an array"))?
+      .as_slice(),
+    None => &[],
+  };
+
+  let reader = Reader {
+    directed: graph
+      .get("directed")
+      .and_then(Value::as_bool)
+      .unwrap_or(true),
+    edges: index(edges, "edge")?,
+    nodes: index(nodes, "node")?,
+    subgraphs: index(subgraphs, "subgraph")?,
+  };
+
+  // Subgraphs list the subgraphs directly inside them, so the top-level
+  // subgraphs are those with no parent.
+  let mut parents = HashMap::new();
+
+  for &(gvid, subgraph) in &reader.subgraphs {
+    for child in gvids(subgraph, "subgraphs")? {
+      lookup(&reader.subgraphs, "subgraph", child)?;
+
+      if parents.insert(child, gvid).is_some() || child == gvid {
+        return Err(invalid(format!(
+          "subgraph {child} is listed in more than one subgraph"
+        )));
+      }
+    }
+  }
+
+  let top = reader
+    .subgraphs
+    .iter()
+    .map(|(gvid, _)| *gvid)
+    .filter(|gvid| !parents.contains_key(gvid))
+    .collect::<Vec<u64>>();
+
+  let edges = reader
+    .edges
+    .iter()
+    .map(|(gvid, _)| *gvid)
+    .collect::<Vec<u64>>();
+
+  // Every node is declared up front, with its attributes, so that nodes are
+  // created in `_gvid` order.
+  let mut statements = Vec::new();
+
+  for &(gvid, node) in &reader.nodes {
+    statements.push(Statement::Node(NodeStatement {
+      attributes: attributes(node, &[]),
+      id: NodeId {
+        id: Id::new(name(node, "node", gvid)?),
+        port: None,
+        span: 0..0,
+      },
+      span: 0..0,
+    }));
+  }
+
+  let mut rest = reader.statements(graph, &top, &[], &edges)?;
+
+  // Graph attributes come first, so they apply to everything.
+  let assignments = rest
+    .iter()
+    .take_while(|statement| matches!(statement, Statement::Assign(..)))
+    .count();
+
+  statements.splice(0..0, rest.drain(..assignments));
+  statements.extend(rest);
+
+  Ok(Graph {
+    id: id(graph),
+    kind: if reader.directed {
+      GraphKind::Digraph
+    } else {
+      GraphKind::Graph
+    },
+    span: 0..0,
+    statements,
+    strict: graph
+      .get("strict")
+      .and_then(Value::as_bool)
+      .unwrap_or(false),
+  })
+}
+
+pub(crate) fn write(graph: &model::Graph<'_>) -> String {
+  let mut writer = Writer {
+    anonymous: 0,
+    subgraphs: Vec::new(),
+  };
+
+  let mut object = Map::new();
+
+  object.insert("name".into(), writer.name(graph.id.as_ref()).into());
+  object.insert("directed".into(), (graph.kind == GraphKind::Digraph).into());
+  object.insert("strict".into(), graph.strict.into());
+
+  insert_attributes(&mut object, &graph.attributes);
+
+  writer.subgraphs(&graph.subgraphs);
+
+  object.insert("_subgraph_cnt".into(), writer.subgraphs.len().into());
+
+  let mut objects = writer.subgraphs;
+
+  objects.extend(graph.nodes.iter().enumerate().map(|(gvid, node)| {
+    let mut object = Map::new();
+
+    object.insert("_gvid".into(), gvid.into());
+    object.insert("name".into(), node.id.decoded().into());
+
+    insert_attributes(&mut object, &node.attributes);
+
+    Value::Object(object)
+  }));
+
+  if !objects.is_empty() {
+    object.insert("objects".into(), objects.into());
+  }
+
+  let edges = graph
+    .edges
+    .iter()
+    .enumerate()
+    .map(|(gvid, edge)| {
+      let mut object = Map::new();
+
+      object.insert("_gvid".into(), gvid.into());
+      object.insert("tail".into(), edge.tail.into());
+      object.insert("head".into(), edge.head.into());
+
+      insert_attributes(&mut object, &edge.attributes);
+
+      if let Some(tail_port) = &edge.tail_port {
+        object.insert("tailport".into(), port(tail_port).into());
+      }
+
+      if let Some(head_port) = &edge.head_port {
+        object.insert("headport".into(), port(head_port).into());
+      }
+
+      Value::Object(object)
+    })
+    .collect::<Vec<Value>>();
+
+  if !edges.is_empty() {
+    object.insert("edges".into(), edges.into());
+  }
+
+  format!("{:#}\n", Value::Object(object))
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, serde_json::json};
+
+  #[test]
+  fn attributes() {
+    case(
+      "digraph { rankdir=LR; node [shape=box] a [label=\"A \\\"1\\\"\"] a -> b [color=red] }",
+      json!({
+        "name": "%1",
+        "directed": true,
+        "strict": false,
+        "rankdir": "LR",
+        "_subgraph_cnt": 0,
+        "objects": [
+          { "_gvid": 0, "name": "a", "label": "A \"1\"", "shape": "box" },
+          { "_gvid": 1, "name": "b", "shape": "box" },
+        ],
+        "edges": [
+          { "_gvid": 0, "tail": 0, "head": 1, "color": "red" },
+        ],
+      }),
+    );
+  }
+
+  #[track_caller]
+  fn case(src: &str, expected: Value) {
+    let json = to_json(&parse(src).unwrap());
+
+    assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), expected);
+  }
+
+  #[test]
+  fn empty() {
+    case(
+      "strict graph G {}",
+      json!({
+        "name": "G",
+        "directed": false,
+        "strict": true,
+        "_subgraph_cnt": 0,
+      }),
+    );
+  }
+
+  #[test]
+  fn key_order() {
+    assert_eq!(
+      to_json(&parse("graph G { a -- b [w=1] }").unwrap()),
+      r#"{
+  "name": "G",
+  "directed": false,
+  "strict": false,
+  "_subgraph_cnt": 0,
+  "objects": [
+    {
+      "_gvid": 0,
+      "name": "a"
+    },
+    {
+      "_gvid": 1,
+      "name": "b"
+    }
+  ],
+  "edges": [
+    {
+      "_gvid": 0,
+      "tail": 0,
+      "head": 1,
+      "w": "1"
+    }
+  ]
+}
+"#,
+    );
+  }
+
+  #[test]
+  fn ports() {
+    case(
+      "digraph { a:p:n -> b:s }",
+      json!({
+        "name": "%1",
+        "directed": true,
+        "strict": false,
+        "_subgraph_cnt": 0,
+        "objects": [
+          { "_gvid": 0, "name": "a" },
+          { "_gvid": 1, "name": "b" },
+        ],
+        "edges": [
+          {
+            "_gvid": 0,
+            "tail": 0,
+            "head": 1,
+            "tailport": "p:n",
+            "headport": "s",
+          },
+        ],
+      }),
+    );
+  }
+
+  #[test]
+  fn read_errors() {
+    #[track_caller]
+    fn case(src: &str, message: &str, span: Range<usize>) {
+      let error = read(src).unwrap_err();
+      assert_eq!(error.kind, ErrorKind::InvalidJson);
+      assert_eq!(error.message, message);
+      assert_eq!(error.span, span);
+    }
+
+    case(
+      "{\n  \"name\": }",
+      "expected value at line 2 column 11",
+      12..12,
+    );
+    case("[]", "a Graphviz JSON document must be an object", 0..0);
+    case(
+      r#"{ "_subgraph_cnt": 1 }"#,
+      "`_subgraph_cnt` is 1, but there are only 0 objects",
+      0..0,
+    );
+    case(
+      r#"{ "objects": [{ "name": "a" }], "edges": [{ "tail": 0, "head": 1 }] }"#,
+      "there is no node with `_gvid` 1",
+      0..0,
+    );
+    case(
+      r#"{ "objects": [{ "_gvid": 0 }] }"#,
+      "node 0 has no `name`",
+      0..0,
+    );
+    case(
+      r#"{ "_subgraph_cnt": 2, "objects": [{ "subgraphs": [1] }, { "subgraphs": [1] }] }"#,
+      "subgraph 1 is listed in more than one subgraph",
+      0..0,
+    );
+  }
+
+  #[test]
+  fn read_graphviz_output() {
+    let graph = read(
+      r##"{
+  "name": "%3",
+  "directed": false,
+  "strict": false,
+  "_draw_": [{ "op": "c", "grad": "none", "color": "#fffffe00" }],
+  "bb": "0,0,62,108",
+  "xdotversion": "1.7",
+  "_subgraph_cnt": 1,
+  "objects": [
+    {
+      "name": "cluster_x",
+      "bb": "8,8,54,100",
+      "label": "X",
+      "_gvid": 0,
+      "nodes": [1]
+    },
+    {
+      "_gvid": 0,
+      "name": "node a",
+      "height": 0.5,
+      "pos": "27,90"
+    },
+    {
+      "_gvid": 1,
+      "name": "b",
+      "pos": "27,18"
+    }
+  ],
+  "edges": [
+    {
+      "_gvid": 0,
+      "tail": 0,
+      "head": 1,
+      "tailport": "s",
+      "pos": "27,71.7 27,36.3"
+    }
+  ]
+}"##,
+    )
+    .unwrap();
+
+    assert_eq!(
+      graph.to_string(),
+      r#"graph {
+  bb="0,0,62,108";
+  xdotversion=1.7;
+  "node a" [height=0.5, pos="27,90"];
+  b [pos="27,18"];
+  subgraph cluster_x {
+    bb="8,8,54,100";
+    label=X;
+    b;
+  }
+  "node a":s -- b [pos="27,71.7 27,36.3"];
+}"#,
+    );
+  }
+
+  #[test]
+  fn round_trip() {
+    #[track_caller]
+    fn case(src: &str) {
+      let json = to_json(&parse(src).unwrap());
+
+      let graph = read(&json).unwrap();
+
+      assert_eq!(to_json(&graph), json);
+
+      let reparsed = graph.to_string();
+
+      assert_eq!(to_json(&parse(&reparsed).unwrap()), json);
+    }
+
+    case("digraph {}");
+    case("strict graph G { a -- b; b -- a }");
+    case(
+      "digraph { node [shape=box] c; a:p:n -> b:s [label=\"x \\\"y\\\"\"] }",
+    );
+    case(
+      "digraph G { subgraph cluster_0 { label=A; a -> b; { rank=same; c } } subgraph cluster_1 { d } b -> d }",
+    );
+    case(
+      "digraph { a -> b; subgraph s { b -> c; subgraph t { c -> d } } d -> a }",
+    );
+  }
+
+  #[test]
+  fn subgraphs() {
+    case(
+      "digraph G { subgraph cluster_0 { label=A; a -> b; { rank=same; c } } subgraph cluster_1 { d } b -> d }",
+      json!({
+        "name": "G",
+        "directed": true,
+        "strict": false,
+        "_subgraph_cnt": 3,
+        "objects": [
+          {
+            "name": "cluster_0",
+            "label": "A",
+            "_gvid": 0,
+            "subgraphs": [1],
+            "nodes": [0, 1, 2],
+            "edges": [0],
+          },
+          {
+            "name": "%1",
+            "label": "A",
+            "rank": "same",
+            "_gvid": 1,
+            "nodes": [2],
+          },
+          { "name": "cluster_1", "_gvid": 2, "nodes": [3] },
+          { "_gvid": 0, "name": "a" },
+          { "_gvid": 1, "name": "b" },
+          { "_gvid": 2, "name": "c" },
+          { "_gvid": 3, "name": "d" },
+        ],
+        "edges": [
+          { "_gvid": 0, "tail": 0, "head": 1 },
+          { "_gvid": 1, "tail": 1, "head": 3 },
+        ],
+      }),
+    );
+  }
+}
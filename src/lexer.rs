@@ -3,13 +3,20 @@ use super::*;
 pub(crate) type Span = SimpleSpan<usize>;
 pub(crate) type Spanned<'src> = (Token<'src>, Span);
 
-pub(crate) fn lex(src: &str) -> ParseResult<Vec<Spanned<'_>>, Rich<'_, char>> {
-  lexer().parse(src)
+pub(crate) fn lex(
+  src: &str,
+) -> ParseResult<Vec<Spanned<'_>>, SyntaxError<'_, char>> {
+  lexer(src).parse(src)
 }
 
-fn lexer<'src>()
--> impl Parser<'src, &'src str, Vec<Spanned<'src>>, extra::Err<Rich<'src, char>>>
-{
+fn lexer<'src>(
+  src: &'src str,
+) -> impl Parser<
+  'src,
+  &'src str,
+  Vec<Spanned<'src>>,
+  extra::Err<SyntaxError<'src, char>>,
+> {
   let digits = text::digits(10).to_slice();
 
   let number = just('-')
@@ -26,60 +33,82 @@ fn lexer<'src>()
 
   let escape = just('\\').then(any());
 
-  let string = just('"')
-    .then(none_of("\"\\").ignored().or(escape.ignored()).repeated())
-    .then(just('"'))
-    .to_slice()
-    .map(|string: &str| Token::String(&string[1..string.len() - 1]));
+  let character = none_of("\"\\").ignored().or(escape.ignored());
 
-  let html_string = custom(|input: &mut InputRef<'src, '_, &'src str, _>| {
-    let before = input.cursor();
+  let quoted = just('"').then(character.repeated()).then(just('"'));
 
-    match input.next() {
-      Some('<') => {}
-      found => {
-        let span = input.span_since(&before);
+  // Quoted strings joined with `+` form a single string, whose raw value runs
+  // from after the first opening quote to before the last closing quote.
+  let string = quoted
+    .then(
+      text::whitespace()
+        .then(just('+'))
+        .then(text::whitespace())
+        .then(quoted)
+        .repeated(),
+    )
+    .to_slice()
+    .map(|string: &str| Token::String(&string[1..string.len() - 1]));
 
-        return Err(Rich::custom(
-          span,
-          format!(
-            "expected '<', found {}",
-            found.map_or("end of input".into(), |c: char| format!("'{c}'"))
-          ),
-        ));
+  // Strings and comments that run to the end of the input are reported, but
+  // still lexed, so that the rest of the input is checked too.
+  let unclosed_string = just('"')
+    .then(character.repeated())
+    .then(end())
+    .to_slice()
+    .validate(|string: &str, e, emitter| {
+      emitter.emit(unclosed(ErrorKind::UnclosedString, e.span(), "\"", "\""));
+      Token::String(&string[1..])
+    });
+
+  let html_string = just('<')
+    .ignore_then(custom(|input: &mut InputRef<'src, '_, &'src str, _>| {
+      let mut depth = 1u32;
+
+      while depth > 0 {
+        match input.next() {
+          Some('<') => depth += 1,
+          Some('>') => depth -= 1,
+          Some(_) => {}
+          None => return Ok(false),
+        }
       }
-    }
 
-    let mut depth = 1u32;
-
-    while depth > 0 {
-      match input.next() {
-        Some('<') => depth += 1,
-        Some('>') => depth -= 1,
-        Some(_) => {}
-        None => {
-          return Err(Rich::custom(
-            input.span_since(&before),
-            "unclosed HTML string",
-          ));
-        }
+      Ok::<_, SyntaxError<'src, char>>(true)
+    }))
+    .validate(|closed, e, emitter| {
+      let slice: &str = e.slice();
+
+      if closed {
+        return Token::HtmlString(&slice[1..slice.len() - 1]);
       }
-    }
 
-    let slice = input.slice_since(&before..);
+      emitter.emit(unclosed(ErrorKind::UnclosedHtmlString, e.span(), "<", ">"));
 
-    Ok(Token::HtmlString(&slice[1..slice.len() - 1]))
-  });
+      Token::HtmlString(&slice[1..])
+    });
 
-  let ident = text::ascii::ident().map(|string: &str| match string {
-    _ if string.eq_ignore_ascii_case("strict") => Token::Strict,
-    _ if string.eq_ignore_ascii_case("graph") => Token::Graph,
-    _ if string.eq_ignore_ascii_case("digraph") => Token::Digraph,
-    _ if string.eq_ignore_ascii_case("node") => Token::Node,
-    _ if string.eq_ignore_ascii_case("edge") => Token::Edge,
-    _ if string.eq_ignore_ascii_case("subgraph") => Token::Subgraph,
-    _ => Token::Ident(string),
-  });
+  // DOT identifiers may contain any byte from `\200` to `\377`, which in
+  // UTF-8 source means any non-ASCII character.
+  let ident = any()
+    .filter(|c: &char| c.is_ascii_alphabetic() || *c == '_' || !c.is_ascii())
+    .then(
+      any()
+        .filter(|c: &char| {
+          c.is_ascii_alphanumeric() || *c == '_' || !c.is_ascii()
+        })
+        .repeated(),
+    )
+    .to_slice()
+    .map(|string: &str| match string {
+      _ if string.eq_ignore_ascii_case("strict") => Token::Strict,
+      _ if string.eq_ignore_ascii_case("graph") => Token::Graph,
+      _ if string.eq_ignore_ascii_case("digraph") => Token::Digraph,
+      _ if string.eq_ignore_ascii_case("node") => Token::Node,
+      _ if string.eq_ignore_ascii_case("edge") => Token::Edge,
+      _ if string.eq_ignore_ascii_case("subgraph") => Token::Subgraph,
+      _ => Token::Ident(string),
+    });
 
   let arrow = just("->").to(Token::Arrow);
   let dashdash = just("--").to(Token::DashDash);
@@ -97,41 +126,127 @@ fn lexer<'src>()
 
   let line_comment = just("//")
     .then(any().and_is(just('\n').not()).repeated())
-    .ignored()
-    .padded();
+    .to_slice();
 
   let block_comment = just("/*")
     .then(any().and_is(just("*/").not()).repeated())
     .then(just("*/"))
-    .ignored()
-    .padded();
-
-  let comment = line_comment.or(block_comment).repeated();
-
-  let token =
-    choice((arrow, dashdash, number, string, html_string, ident, punct));
-
-  comment
-    .ignore_then(
-      token
-        .map_with(|tok, e| (tok, e.span()))
-        .padded()
-        .padded_by(comment)
-        .repeated()
-        .collect(),
-    )
+    .to_slice();
+
+  let unclosed_comment = just("/*").then(any().repeated()).to_slice().validate(
+    |comment, e, emitter| {
+      emitter.emit(unclosed(ErrorKind::UnclosedComment, e.span(), "/*", "*/"));
+      comment
+    },
+  );
+
+  // Like Graphviz, treat lines starting with `#`, such as C preprocessor line
+  // markers, as comments, but only if the `#` is at the start of the line.
+  let preprocessor_line = just('#')
+    .then(any().and_is(just('\n').not()).repeated())
+    .to_slice()
+    .try_map(move |line: &'src str, span: Span| {
+      if src[..span.start].is_empty() || src[..span.start].ends_with('\n') {
+        Ok(line)
+      } else {
+        Err(SyntaxError::custom(
+          ErrorKind::MisplacedPreprocessorLine,
+          span,
+          "`#` lines must start at the beginning of a line",
+        ))
+      }
+    });
+
+  let comment = line_comment
+    .or(block_comment)
+    .or(unclosed_comment)
+    .or(preprocessor_line)
+    .map(Token::Comment);
+
+  let token = choice((
+    comment,
+    arrow,
+    dashdash,
+    number,
+    string,
+    unclosed_string,
+    html_string,
+    ident,
+    punct,
+  ));
+
+  token
+    .map_with(|tok, e| (tok, e.span()))
+    .padded()
+    .repeated()
+    .collect()
+    .padded()
     .then_ignore(end())
 }
 
+/// An error for a string or comment at `span` that runs to the end of the
+/// input, pointing at its `open` delimiter.
+fn unclosed<'src>(
+  kind: ErrorKind,
+  span: Span,
+  open: &str,
+  close: &str,
+) -> SyntaxError<'src, char> {
+  SyntaxError::custom(
+    kind,
+    (span.start..span.start + open.len()).into(),
+    match kind {
+      ErrorKind::UnclosedComment => "unclosed comment",
+      ErrorKind::UnclosedHtmlString => "unclosed HTML string",
+      _ => "unclosed string",
+    },
+  )
+  .with_help(format!("add a closing `{close}`"))
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
 
   #[test]
   fn comments() {
-    assert_eq!(lex("// foo\nbar"), vec![Token::Ident("bar")]);
-    assert_eq!(lex("/* foo */ bar"), vec![Token::Ident("bar")]);
-    assert_eq!(lex("/* foo\nbar */ baz"), vec![Token::Ident("baz")]);
+    assert_eq!(
+      lex("// foo\nbar"),
+      vec![Token::Comment("// foo"), Token::Ident("bar")],
+    );
+    assert_eq!(
+      lex("/* foo */ bar"),
+      vec![Token::Comment("/* foo */"), Token::Ident("bar")],
+    );
+    assert_eq!(
+      lex("/* foo\nbar */ baz"),
+      vec![Token::Comment("/* foo\nbar */"), Token::Ident("baz")],
+    );
+    assert_eq!(
+      lex("a // foo\n// bar\n"),
+      vec![
+        Token::Ident("a"),
+        Token::Comment("// foo"),
+        Token::Comment("// bar"),
+      ],
+    );
+  }
+
+  #[test]
+  fn comments_preprocessor() {
+    assert_eq!(
+      lex("# 1 \"file.c\"\ndigraph {\n#pragma foo\n}\n#"),
+      vec![
+        Token::Comment("# 1 \"file.c\""),
+        Token::Digraph,
+        Token::OpenBrace,
+        Token::Comment("#pragma foo"),
+        Token::CloseBrace,
+        Token::Comment("#"),
+      ],
+    );
+    assert!(super::lex("a # not a comment").into_result().is_err());
+    assert!(super::lex("a\n  # indented").into_result().is_err());
   }
 
   #[test]
@@ -192,6 +307,31 @@ mod tests {
     case("_bar", "_bar");
     case("a1", "a1");
     case("node_name", "node_name");
+    case("naïve", "naïve");
+    case("日本", "日本");
+    case("_é1", "_é1");
+    case("Ωmega", "Ωmega");
+    case("\u{80}\u{ff}", "\u{80}\u{ff}");
+  }
+
+  #[test]
+  fn identifiers_non_ascii() {
+    assert_eq!(
+      lex("graph { café -- 日本 [ラベル=données] }"),
+      vec![
+        Token::Graph,
+        Token::OpenBrace,
+        Token::Ident("café"),
+        Token::DashDash,
+        Token::Ident("日本"),
+        Token::OpenBracket,
+        Token::Ident("ラベル"),
+        Token::Equals,
+        Token::Ident("données"),
+        Token::CloseBracket,
+        Token::CloseBrace,
+      ],
+    );
   }
 
   #[test]
@@ -225,7 +365,7 @@ mod tests {
   }
 
   fn lex(input: &str) -> Vec<Token<'_>> {
-    lexer()
+    lexer(input)
       .parse(input)
       .into_result()
       .unwrap()
@@ -286,5 +426,8 @@ mod tests {
     case(r#""foo bar""#, "foo bar");
     case(r#""foo\"bar""#, r#"foo\"bar"#);
     case(r#""foo\\bar""#, r"foo\\bar");
+    case(r#""foo" + "bar""#, r#"foo" + "bar"#);
+    case("\"foo\"\n+\n\"bar\"+\"baz\"", "foo\"\n+\n\"bar\"+\"baz");
+    case("\"foo\\\nbar\"", "foo\\\nbar");
   }
 }
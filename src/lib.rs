@@ -1,23 +1,28 @@
 use {
   chumsky::{
-    input::{InputRef, ValueInput},
+    extra::ParserExtra,
+    input::{InputRef, MapExtra, ValueInput},
     prelude::*,
   },
-  lexer::Span,
+  error::SyntaxError,
+  lexer::{Span, Spanned},
   std::{
+    borrow::Cow,
     fmt::{self, Display, Formatter},
     ops::Range,
   },
   token::Token,
+  value::Value,
 };
 
 pub use {
   ast::{
-    Attribute, AttributeStatement, AttributeTarget, EdgeOperation,
-    EdgeStatement, EdgeTarget, Graph, GraphKind, Id, NodeId, NodeStatement,
-    Port, Statement, Subgraph,
+    Attribute, AttributeStatement, AttributeTarget, Edge, EdgeOperation,
+    EdgeStatement, EdgeTarget, Graph, GraphKind, Id, IdKind, NodeId,
+    NodeStatement, Port, Statement, Subgraph,
   },
-  parser::ParseError,
+  builder::{Endpoint, GraphBuilder},
+  error::{ErrorKind, Label, ParseError, Severity},
 };
 
 #[macro_export]
@@ -35,23 +40,116 @@ macro_rules! assert_matches {
 }
 
 mod ast;
+mod builder;
+mod colors;
+pub mod cst;
+mod error;
+mod formatter;
+mod json;
 mod lexer;
+pub mod model;
 mod parser;
+pub mod schema;
 mod token;
+mod validator;
+pub mod value;
+pub mod visit;
+
+/// Format Graphviz DOT source canonically.
+///
+/// The source is parsed and written back out with two-space indentation, one
+/// statement per line, a semicolon after every statement and attribute lists
+/// normalized to a single comma-separated `[...]` list. Unlike printing a
+/// parsed [`Graph`], comments in the source are preserved. Source containing
+/// several graphs is formatted graph by graph, and errors from every graph are
+/// returned together.
+///
+/// Formatting already formatted source returns it unchanged.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(
+///   dotgraph::format("digraph{a->b // edge\n}").unwrap(),
+///   "digraph {\n  a -> b; // edge\n}\n",
+/// );
+/// ```
+pub fn format(src: &str) -> Result<String, Vec<ParseError>> {
+  formatter::format(src)
+}
+
+/// Read a graph from a Graphviz JSON document, as written by `dot -Tjson`,
+/// `dot -Tjson0` or [`to_json`].
+///
+/// The graph's subgraphs are rebuilt from the subgraph, node and edge
+/// `_gvid`s each subgraph lists, with every node and edge placed in the
+/// innermost subgraph that contains it. All nodes are declared at the start
+/// of the graph, with their attributes, in `_gvid` order, so resolving the
+/// graph into a [`model::Graph`] numbers nodes as the document does. Edges
+/// are placed so that they keep their order too, as long as the subgraphs
+/// holding them were declared in that order.
+///
+/// Attributes with string, number or boolean values become attributes of the
+/// graph, subgraph, node or edge they are on, and `tailport` and `headport`
+/// become edge ports. Other values, such as the drawing operations that
+/// `-Tjson` adds, are left out. Graphs and subgraphs whose name starts with
+/// `%`, which Graphviz uses for anonymous graphs, are anonymous.
+///
+/// The graph does not borrow `src`, and all of its spans are empty. Errors
+/// have kind [`ErrorKind::InvalidJson`], and only point into `src` if it is
+/// not valid JSON.
+///
+/// # Examples
+///
+/// ```
+/// let graph = dotgraph::from_json(
+///   r#"{
+///     "name": "G",
+///     "directed": true,
+///     "_subgraph_cnt": 0,
+///     "objects": [
+///       { "_gvid": 0, "name": "a", "shape": "box" },
+///       { "_gvid": 1, "name": "b" }
+///     ],
+///     "edges": [{ "_gvid": 0, "tail": 0, "head": 1 }]
+///   }"#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(
+///   graph.to_string(),
+///   "digraph G {\n  a [shape=box];\n  b;\n  a -> b;\n}",
+/// );
+/// ```
+pub fn from_json(src: &str) -> Result<Graph<'static>, ParseError> {
+  json::read(src)
+}
 
 /// Parse Graphviz DOT source into a syntax tree.
 ///
 /// This is the crate's primary public API. It tokenizes the input and then
-/// parses it as a single DOT graph.
+/// parses it as a single DOT graph. Use [`parse_all`] for source that may
+/// contain several graphs.
 ///
 /// The returned [`Graph`] borrows all string slices directly from `src`, so
-/// the source string must outlive the parsed value.
+/// the source string must outlive the parsed value, unless it is converted
+/// with [`Graph::into_owned`]. Every node in the tree carries a `span`, a byte
+/// range into `src` that it was parsed from.
 ///
 /// On failure, this function returns all lexer and parser errors it can
 /// recover, each with:
 ///
+/// - `kind`: an [`ErrorKind`] to match on
 /// - `message`: a human-readable description of the problem
 /// - `span`: a byte range in the original source where the problem occurred
+/// - `labels`, `help` and `notes`: related locations and hints, such as the
+///   opening brace of a block that is never closed
+///
+/// The parser recovers from a malformed statement by skipping to the next `;`
+/// or the end of the enclosing block, and from a malformed attribute by
+/// skipping to the next `,`, `;` or `]`, so independent problems in a file are
+/// all reported at once. A missing closing brace at the end of the input is
+/// reported without hiding the errors before it.
 ///
 /// # Examples
 ///
@@ -64,6 +162,151 @@ mod token;
 /// let error = dotgraph::parse("digraph {").unwrap_err();
 /// assert!(!error.is_empty());
 /// ```
+///
+/// ```
+/// let errors = dotgraph::parse("digraph { a -> ; b [color=] }").unwrap_err();
+/// assert_eq!(errors.len(), 2);
+/// ```
 pub fn parse(src: &str) -> Result<Graph<'_>, Vec<ParseError>> {
   parser::parse(src)
 }
+
+/// Parse Graphviz DOT source containing any number of graphs, one after
+/// another, as accepted by the Graphviz command line tools.
+///
+/// Each graph is parsed on its own, so a syntax error in one graph does not
+/// prevent the graphs after it from being parsed. The result has one entry per
+/// graph in the source, holding either the graph or the errors found in it.
+/// Source containing only whitespace and comments has no graphs.
+///
+/// If the source cannot be tokenized, the result is a single entry holding the
+/// lexer errors.
+///
+/// # Examples
+///
+/// ```
+/// let graphs = dotgraph::parse_all("graph { a } digraph { b -> }");
+///
+/// assert_eq!(graphs.len(), 2);
+/// assert!(graphs[0].is_ok());
+/// assert!(graphs[1].is_err());
+/// ```
+pub fn parse_all(src: &str) -> Vec<Result<Graph<'_>, Vec<ParseError>>> {
+  parser::parse_all(src)
+}
+
+/// Parse Graphviz DOT source into a lossless [concrete syntax tree](cst).
+///
+/// The tree keeps every token of the source, including whitespace and
+/// comments, and prints back as the exact source it was parsed from. Its root
+/// is a [`cst::NodeKind::File`] node holding every graph in the source, each
+/// of which converts to the [`Graph`] that [`parse_all`] would return.
+///
+/// If any graph in the source fails to parse, all errors are returned, as with
+/// [`format()`].
+///
+/// # Examples
+///
+/// ```
+/// let src = "digraph { a -> b } // done\n";
+///
+/// let tree = dotgraph::parse_cst(src).unwrap();
+///
+/// assert_eq!(tree.to_string(), src);
+/// assert_eq!(tree.graphs()[0], dotgraph::parse(src).unwrap());
+/// ```
+pub fn parse_cst(src: &str) -> Result<cst::Node<'_>, Vec<ParseError>> {
+  cst::parse(src)
+}
+
+/// Parse Graphviz DOT source into a syntax tree, keeping whatever could be
+/// parsed when there are errors.
+///
+/// Like [`parse`], this recovers from errors in statements and attribute
+/// lists, but instead of discarding the result it returns the partial graph
+/// alongside every error found, leaving out the statements and attributes that
+/// failed to parse. This lets tools such as outlines, linters and formatters
+/// keep working on the valid parts of a file while it is being edited.
+///
+/// The graph is `None` when nothing useful could be recovered, such as when the
+/// source cannot be tokenized or has no graph header. When the error list is
+/// empty, the graph is exactly what [`parse`] would have returned.
+///
+/// # Examples
+///
+/// ```
+/// let (graph, errors) = dotgraph::parse_partial("digraph { a -> b; c -> }");
+///
+/// assert_eq!(graph.unwrap().statements.len(), 2);
+/// assert_eq!(errors.len(), 1);
+/// ```
+pub fn parse_partial(src: &str) -> (Option<Graph<'_>>, Vec<ParseError>) {
+  parser::parse_partial(src)
+}
+
+/// Write a graph in the JSON format of Graphviz's `json0` output, for tools
+/// that consume Graphviz JSON.
+///
+/// The graph is [resolved](model::Graph) first, so the output has one object
+/// per node and per edge, with attribute defaults applied, as Graphviz writes
+/// it:
+///
+/// - The graph object has the graph's `name`, whether it is `directed` and
+///   `strict`, its attributes, `_subgraph_cnt`, the number of subgraphs,
+///   `objects`, the subgraphs followed by the nodes, and `edges`.
+/// - Subgraphs, nodes and edges each have a `_gvid`, their index among the
+///   objects of their kind. Subgraphs are numbered in pre-order, and list the
+///   `_gvid`s of their direct `subgraphs`, and of every node and edge in them
+///   as `nodes` and `edges`.
+/// - Edges refer to their `tail` and `head` nodes by `_gvid`, and carry their
+///   ports as `tailport` and `headport` attributes.
+/// - Attribute values are strings, as decoded by [`Id::decoded`]. Valueless
+///   attributes are `"true"`.
+///
+/// Anonymous graphs and subgraphs are named `%1`, `%2` and so on, in order,
+/// which may not match the names Graphviz gives them. Since this crate does
+/// not lay out graphs, positions are included only for graphs that carry
+/// layout attributes such as `pos` and `bb`, such as the output of `dot
+/// -Tdot`, and Graphviz's `json` format, which adds drawing operations, is
+/// written the same way.
+///
+/// # Examples
+///
+/// ```
+/// let graph = dotgraph::parse("digraph { a -> b }").unwrap();
+///
+/// let json = dotgraph::to_json(&graph);
+///
+/// assert!(json.contains(r#""tail": 0"#));
+/// ```
+pub fn to_json(graph: &Graph) -> String {
+  model::Graph::from(graph).to_json()
+}
+
+/// Check a parsed graph for errors that the DOT grammar alone does not rule
+/// out, but which Graphviz rejects, and for likely mistakes that it accepts.
+///
+/// This reports every edge operator that does not match the kind of graph it
+/// appears in, `->` in a `graph` or `--` in a `digraph`, as an error spanning
+/// the operator.
+///
+/// It also reports these [warnings](Severity::Warning), about attributes that
+/// Graphviz accepts but ignores:
+///
+/// - attributes Graphviz does not know, spanning the key, with a suggestion
+///   when the name looks like a typo of a known attribute
+/// - attributes set on an element they do not apply to, such as `rankdir` on a
+///   node, spanning the key
+/// - attribute values that do not match the attribute's type, spanning the
+///   value, as described by [`schema`]. An empty value stands for the
+///   attribute's default, and is always accepted
+///
+/// # Examples
+///
+/// ```
+/// let graph = dotgraph::parse("graph { a -> b }").unwrap();
+/// assert_eq!(dotgraph::validate(&graph).len(), 1);
+/// ```
+pub fn validate(graph: &Graph) -> Vec<ParseError> {
+  validator::validate(graph)
+}
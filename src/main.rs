@@ -1,36 +1,540 @@
 use {
   ariadne::{Label, Report, ReportKind, Source},
-  dotgraph::parse,
-  std::{env, fs, process},
+  clap::{Parser, ValueEnum},
+  dotgraph::{
+    Attribute, AttributeStatement, AttributeTarget, Graph, Id, ParseError,
+    Severity, Statement, format, model, parse_all, validate,
+  },
+  serde_json::{Value, json},
+  std::{
+    fs::{self, File},
+    io::{self, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+    process,
+  },
 };
 
+#[derive(Parser)]
+#[command(about, version)]
+struct Arguments {
+  /// Write each graph to a file named after its input file and the output
+  /// format, such as `graph.dot.json`
+  #[arg(short = 'O', conflicts_with = "output")]
+  auto_output: bool,
+  /// Set a default edge attribute, which edges in the graph can override
+  #[arg(short = 'E', value_name = "NAME[=VALUE]", value_parser = attribute)]
+  edge_attributes: Vec<Attribute<'static>>,
+  /// Set a default graph attribute, which the graph can override
+  #[arg(short = 'G', value_name = "NAME[=VALUE]", value_parser = attribute)]
+  graph_attributes: Vec<Attribute<'static>>,
+  /// How to report errors. JSON and SARIF messages are written to standard
+  /// output, so without `-o` or `-O` graphs are only checked, and not written
+  #[arg(global = true, long, value_enum, default_value_t = MessageFormat::Human)]
+  message_format: MessageFormat,
+  /// Set a default node attribute, which nodes in the graph can override
+  #[arg(short = 'N', value_name = "NAME[=VALUE]", value_parser = attribute)]
+  node_attributes: Vec<Attribute<'static>>,
+  /// Write every graph to this file, instead of standard output
+  #[arg(short = 'o')]
+  output: Option<PathBuf>,
+  /// Write each graph in this format
+  #[arg(short = 'T', value_enum, default_value_t = OutputFormat::Dot)]
+  output_format: OutputFormat,
+  /// DOT files to parse, each of which may contain several graphs. Standard
+  /// input is read if none are given
+  paths: Vec<PathBuf>,
+  #[command(subcommand)]
+  subcommand: Option<Subcommand>,
+}
+
+/// Where `parse` writes each graph.
+enum Destination {
+  /// A file per graph, named by `-O`
+  Auto,
+  /// Nowhere, since machine-readable messages own standard output, so graphs
+  /// are only checked
+  Discard,
+  /// A single file, given by `-o`
+  File { file: File, path: PathBuf },
+  /// Standard output
+  Stdout,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum MessageFormat {
+  /// Reports for people, on standard error
+  Human,
+  /// One JSON object per error and line, on standard output
+  Json,
+  /// A SARIF 2.1.0 log of every error, on standard output
+  Sarif,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputFormat {
+  /// DOT, as written by `dot -Tcanon`
+  Canon,
+  /// DOT, as written by `dot -Tdot`
+  #[value(alias = "gv")]
+  Dot,
+  /// Graphviz JSON, as written by `dot -Tjson0`. Without layout there is
+  /// nothing for `dot -Tjson` to add, so `json` is an alias for it
+  #[value(alias = "json")]
+  Json0,
+}
+
+/// Reports errors in the chosen message format. SARIF results are collected
+/// and written out as a single log by `finish`.
+struct Reporter {
+  format: MessageFormat,
+  results: Vec<Value>,
+}
+
+#[derive(clap::Subcommand)]
+enum Subcommand {
+  /// Format DOT files in place
+  Fmt {
+    /// Report files that are not formatted and exit with a non-zero status,
+    /// instead of rewriting them
+    #[arg(long)]
+    check: bool,
+    /// DOT files to format
+    #[arg(required = true)]
+    paths: Vec<PathBuf>,
+  },
+}
+
+impl OutputFormat {
+  /// The graph in this format, resolved with the `-G`, `-N` and `-E`
+  /// `overrides`. Without layout, `dot` has no positions to add, so it is the
+  /// same as `canon`.
+  fn render(self, graph: &Graph, overrides: &[AttributeStatement]) -> String {
+    match self {
+      // Like Graphviz, write the overrides as attribute statements at the
+      // start of the graph, which resolve the same way.
+      Self::Canon | Self::Dot => {
+        let mut graph = graph.clone();
+
+        graph
+          .statements
+          .splice(0..0, overrides.iter().cloned().map(Statement::Attr));
+
+        format!("{graph}\n")
+      }
+      Self::Json0 => {
+        let mut defaults = model::Defaults::default();
+
+        for statement in overrides {
+          defaults.set(&statement.target, &statement.attributes);
+        }
+
+        model::Graph::with_defaults(graph, defaults).to_json()
+      }
+    }
+  }
+}
+
+impl Reporter {
+  fn finish(self) {
+    if self.format != MessageFormat::Sarif {
+      return;
+    }
+
+    let mut codes = self
+      .results
+      .iter()
+      .map(|result| result["ruleId"].clone())
+      .collect::<Vec<_>>();
+
+    codes.sort_by_key(ToString::to_string);
+    codes.dedup();
+
+    let log = json!({
+      "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+      "version": "2.1.0",
+      "runs": [{
+        "tool": {
+          "driver": {
+            "name": env!("CARGO_BIN_NAME"),
+            "version": env!("CARGO_PKG_VERSION"),
+            "rules": codes
+              .into_iter()
+              .map(|code| json!({ "id": code }))
+              .collect::<Vec<_>>(),
+          },
+        },
+        "columnKind": "unicodeCodePoints",
+        "results": self.results,
+      }],
+    });
+
+    println!("{log:#}");
+  }
+
+  /// Reports an error reading or writing the file at `path`, which has no
+  /// source to point into.
+  fn io_error(&mut self, path: &Path, err: &io::Error) {
+    let path = path.display().to_string();
+    let message = err.to_string();
+
+    match self.format {
+      MessageFormat::Human => eprintln!("{path}: {message}"),
+      MessageFormat::Json => println!(
+        "{}",
+        json!({
+          "file": path,
+          "severity": Severity::Error.to_string(),
+          "code": "io-error",
+          "message": message,
+        })
+      ),
+      MessageFormat::Sarif => self.results.push(json!({
+        "ruleId": "io-error",
+        "level": Severity::Error.to_string(),
+        "message": { "text": message },
+        "locations": [{
+          "physicalLocation": {
+            "artifactLocation": { "uri": path },
+          },
+        }],
+      })),
+    }
+  }
+
+  fn report(&mut self, path: &Path, src: &str, errors: &[ParseError]) {
+    let path = path.display().to_string();
+
+    for error in errors {
+      match self.format {
+        MessageFormat::Human => human(&path, src, error),
+        MessageFormat::Json => println!(
+          "{}",
+          json!({
+            "file": path,
+            "severity": error.severity.to_string(),
+            "code": error.kind.code(),
+            "message": error.message,
+            "span": span(src, &error.span),
+            "labels": error
+              .labels
+              .iter()
+              .map(|label| json!({
+                "message": label.message,
+                "span": span(src, &label.span),
+              }))
+              .collect::<Vec<_>>(),
+            "help": error.help,
+            "notes": error.notes,
+          })
+        ),
+        MessageFormat::Sarif => self.results.push(json!({
+          "ruleId": error.kind.code(),
+          "level": error.severity.to_string(),
+          "message": { "text": error.message },
+          "locations": [location(&path, src, &error.span)],
+          "relatedLocations": error
+            .labels
+            .iter()
+            .map(|label| {
+              let mut location = location(&path, src, &label.span);
+              location["message"] = json!({ "text": label.message });
+              location
+            })
+            .collect::<Vec<_>>(),
+          "properties": {
+            "help": error.help,
+            "notes": error.notes,
+          },
+        })),
+      }
+    }
+  }
+}
+
+/// Parses a `-G`, `-N` or `-E` attribute. Like Graphviz, an attribute without
+/// a value is set to `true`.
+fn attribute(attribute: &str) -> Result<Attribute<'static>, String> {
+  let (key, value) = match attribute.split_once('=') {
+    Some((key, value)) => (key, Some(value)),
+    None => (attribute, None),
+  };
+
+  if key.is_empty() {
+    return Err("attribute name is empty".into());
+  }
+
+  Ok(Attribute {
+    key: Id::new(key.to_owned()),
+    span: 0..0,
+    value: value.map(|value| Id::new(value.to_owned())),
+  })
+}
+
+fn fmt(reporter: &mut Reporter, check: bool, paths: &[PathBuf]) -> bool {
+  let mut failed = false;
+
+  for path in paths {
+    let src = match fs::read_to_string(path) {
+      Ok(src) => src,
+      Err(err) => {
+        reporter.io_error(path, &err);
+        failed = true;
+        continue;
+      }
+    };
+
+    let formatted = match format(&src) {
+      Ok(formatted) => formatted,
+      Err(errors) => {
+        reporter.report(path, &src, &errors);
+        failed = true;
+        continue;
+      }
+    };
+
+    if formatted == src {
+      continue;
+    }
+
+    if check {
+      eprintln!("{}: not formatted", path.display());
+      failed = true;
+    } else if let Err(err) = fs::write(path, formatted) {
+      reporter.io_error(path, &err);
+      failed = true;
+    }
+  }
+
+  failed
+}
+
+fn human(path: &str, src: &str, error: &ParseError) {
+  let span = error.span.clone();
+
+  let kind = match error.severity {
+    Severity::Error => ReportKind::Error,
+    Severity::Warning => ReportKind::Warning,
+  };
+
+  let mut report = Report::build(kind, (path, span.clone()))
+    .with_message(&error.message)
+    .with_label(Label::new((path, span)).with_message(&error.message))
+    .with_labels(error.labels.iter().map(|label| {
+      Label::new((path, label.span.clone())).with_message(&label.message)
+    }));
+
+  report.with_helps(&error.help);
+  report.with_notes(&error.notes);
+
+  report.finish().eprint((path, Source::from(src))).unwrap();
+}
+
+/// A SARIF location for `span` in the file at `path`.
+fn location(path: &str, src: &str, span: &Range<usize>) -> Value {
+  let (start_line, start_column) = position(src, span.start);
+  let (end_line, end_column) = position(src, span.end);
+
+  json!({
+    "physicalLocation": {
+      "artifactLocation": { "uri": path },
+      "region": {
+        "startLine": start_line,
+        "startColumn": start_column,
+        "endLine": end_line,
+        "endColumn": end_column,
+        "byteOffset": span.start,
+        "byteLength": span.len(),
+      },
+    },
+  })
+}
+
 fn main() {
-  let path = env::args().nth(1).unwrap_or_else(|| {
-    eprintln!("usage: dotgraph <file>");
-    process::exit(1);
-  });
+  let arguments = Arguments::parse();
 
-  let src = fs::read_to_string(&path).unwrap_or_else(|err| {
-    eprintln!("{path}: {err}");
-    process::exit(1);
-  });
+  let mut reporter = Reporter {
+    format: arguments.message_format,
+    results: Vec::new(),
+  };
+
+  let failed = match arguments.subcommand {
+    Some(Subcommand::Fmt { check, paths }) => fmt(&mut reporter, check, &paths),
+    None => {
+      let mut destination = match arguments.output {
+        Some(path) => match File::create(&path) {
+          Ok(file) => Destination::File { file, path },
+          // Without somewhere to write graphs there is nothing to do, but
+          // SARIF still needs its log.
+          Err(err) => {
+            reporter.io_error(&path, &err);
+            reporter.finish();
+            process::exit(1);
+          }
+        },
+        None if arguments.auto_output => Destination::Auto,
+        None if reporter.format != MessageFormat::Human => Destination::Discard,
+        None => Destination::Stdout,
+      };
+
+      // Empty statements are left out, so they do not clutter DOT output.
+      let overrides = [
+        (AttributeTarget::Graph, arguments.graph_attributes),
+        (AttributeTarget::Node, arguments.node_attributes),
+        (AttributeTarget::Edge, arguments.edge_attributes),
+      ]
+      .into_iter()
+      .filter(|(_, attributes)| !attributes.is_empty())
+      .map(|(target, attributes)| AttributeStatement {
+        attributes,
+        span: 0..0,
+        target,
+      })
+      .collect::<Vec<_>>();
+
+      let mut failed = false;
+
+      if arguments.paths.is_empty() {
+        failed |= parse(
+          &mut reporter,
+          None,
+          &mut destination,
+          arguments.output_format,
+          &overrides,
+        );
+      }
 
-  let ast = parse(&src).unwrap_or_else(|parse_errors| {
-    for error in &parse_errors {
-      let span = error.span.clone();
+      for path in &arguments.paths {
+        failed |= parse(
+          &mut reporter,
+          Some(path),
+          &mut destination,
+          arguments.output_format,
+          &overrides,
+        );
+      }
 
-      Report::build(ReportKind::Error, (path.as_str(), span.clone()))
-        .with_message(&error.message)
-        .with_label(
-          Label::new((path.as_str(), span)).with_message(&error.message),
-        )
-        .finish()
-        .eprint((path.as_str(), Source::from(&src)))
-        .unwrap();
+      failed
     }
+  };
 
+  reporter.finish();
+
+  if failed {
     process::exit(1);
-  });
+  }
+}
+
+/// Parses, validates and writes out each graph in the file at `path`, or in
+/// standard input if there is no path.
+fn parse(
+  reporter: &mut Reporter,
+  path: Option<&Path>,
+  destination: &mut Destination,
+  output_format: OutputFormat,
+  overrides: &[AttributeStatement],
+) -> bool {
+  let (name, result) = match path {
+    Some(path) => (path, fs::read_to_string(path)),
+    None => (Path::new("<stdin>"), io::read_to_string(io::stdin())),
+  };
+
+  let src = match result {
+    Ok(src) => src,
+    Err(err) => {
+      reporter.io_error(name, &err);
+      return true;
+    }
+  };
+
+  let mut failed = false;
+
+  for (index, result) in parse_all(&src).into_iter().enumerate() {
+    let graph = match result {
+      Ok(graph) => graph,
+      Err(errors) => {
+        reporter.report(name, &src, &errors);
+        failed = true;
+        continue;
+      }
+    };
+
+    let errors = validate(&graph);
+
+    reporter.report(name, &src, &errors);
+
+    // Warnings are reported, but like Graphviz, the graph is still written.
+    if errors.iter().any(ParseError::is_error) {
+      failed = true;
+      continue;
+    }
+
+    let output = output_format.render(&graph, overrides);
+
+    let (file, result) = match destination {
+      // Like Graphviz, name outputs `<input>.<format>`, then
+      // `<input>.2.<format>` and so on for later graphs in the same input.
+      Destination::Auto => {
+        let mut file = path
+          .unwrap_or(Path::new("noname.gv"))
+          .as_os_str()
+          .to_owned();
+
+        if index > 0 {
+          file.push(format!(".{}", index + 1));
+        }
+
+        file.push(".");
+        file.push(output_format.to_possible_value().unwrap().get_name());
+
+        let file = PathBuf::from(file);
+        let result = fs::write(&file, output);
+
+        (file, result)
+      }
+      Destination::Discard => continue,
+      Destination::File { file, path } => {
+        (path.clone(), file.write_all(output.as_bytes()))
+      }
+      Destination::Stdout => (
+        PathBuf::from("<stdout>"),
+        io::stdout().write_all(output.as_bytes()),
+      ),
+    };
+
+    if let Err(err) = result {
+      reporter.io_error(&file, &err);
+      failed = true;
+    }
+  }
+
+  failed
+}
+
+/// The one-based line and column of byte `offset` in `src`, with columns
+/// counted in characters.
+fn position(src: &str, offset: usize) -> (usize, usize) {
+  let before = &src[..offset];
+
+  let start = before.rfind('\n').map_or(0, |newline| newline + 1);
+
+  (
+    before.matches('\n').count() + 1,
+    before[start..].chars().count() + 1,
+  )
+}
+
+/// The byte span of an error, with its start and end positions.
+fn span(src: &str, span: &Range<usize>) -> Value {
+  let (line_start, column_start) = position(src, span.start);
+  let (line_end, column_end) = position(src, span.end);
 
-  println!("{ast:#?}");
+  json!({
+    "byte_start": span.start,
+    "byte_end": span.end,
+    "line_start": line_start,
+    "column_start": column_start,
+    "line_end": line_end,
+    "column_end": column_end,
+  })
 }
@@ -0,0 +1,803 @@
+//! A resolved, semantic view of a parsed graph.
+//!
+//! The syntax tree in the crate root mirrors the DOT grammar, leaving
+//! consumers to interpret attribute defaults, subgraph scoping and edge
+//! chains themselves. [`Graph`] does that interpretation once, following
+//! Graphviz semantics:
+//!
+//! - Every node appears once, in order of first mention, no matter how many
+//!   statements refer to it. Nodes, named subgraphs and attributes are matched
+//!   by their [decoded](Id::decoded) names, so `a`, `"a"` and `"" + "a"` all
+//!   refer to the same node.
+//!
+//! - Edge chains such as `a -> {b c} -> d` are expanded into one [`Edge`] per
+//!   tail and head pair, where a subgraph endpoint stands for every node in
+//!   that subgraph.
+//!
+//! - `node [...]` and `edge [...]` defaults apply to nodes and edges created
+//!   after them in the same graph or subgraph, and in subgraphs nested inside
+//!   it. Explicit attributes on a statement override defaults.
+//!
+//! - `graph [...]` and `key=value` statements set attributes of the graph or
+//!   subgraph they appear in. Subgraphs inherit their parent's attributes.
+//!
+//! - A named subgraph that is opened again continues with the defaults it
+//!   had when it was last closed.
+//!
+//! - In a `strict` graph there is at most one edge between any tail and head,
+//!   or between any pair of nodes if the graph is undirected. A repeated edge
+//!   is merged into the first one, with later attributes and ports overriding
+//!   earlier ones. Self-loops are allowed, but are merged like any other
+//!   edge, matching Graphviz's `cgraph` library.
+//!
+//! A graph resolved from a syntax tree converted with
+//! [`into_owned`](ast::Graph::into_owned) does not borrow the source, and is a
+//! `Graph<'static>`.
+//!
+//! With the `serde` feature enabled, resolved graphs serialize like the syntax
+//! tree, as described on [`crate::Graph`]: structs are objects keyed by
+//! field name, and attributes are objects mapping each name to its [`Id`].
+//! Nodes and edges refer to each other by their index in [`Graph::nodes`] and
+//! [`Graph::edges`].
+
+use {
+  super::*,
+  std::collections::{BTreeMap, HashMap},
+};
+
+pub type Attributes<'src> = BTreeMap<String, Id<'src>>;
+
+/// Default attributes for graphs, nodes and edges, as set by `graph [...]`,
+/// `node [...]` and `edge [...]` statements.
+///
+/// Passed to [`Graph::with_defaults`], they act like statements at the very
+/// start of the graph, so anything the graph sets takes priority over them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Defaults<'src> {
+  pub edge: Attributes<'src>,
+  pub graph: Attributes<'src>,
+  pub node: Attributes<'src>,
+}
+
+impl<'src> Defaults<'src> {
+  fn extend(&mut self, other: &Defaults<'src>) {
+    self.edge.extend(other.edge.clone());
+    self.graph.extend(other.graph.clone());
+    self.node.extend(other.node.clone());
+  }
+
+  /// Set the defaults for `target`, as an attribute statement would.
+  pub fn set(
+    &mut self,
+    target: &AttributeTarget,
+    attributes: &[Attribute<'src>],
+  ) {
+    let defaults = match target {
+      AttributeTarget::Edge => &mut self.edge,
+      AttributeTarget::Graph => &mut self.graph,
+      AttributeTarget::Node => &mut self.node,
+    };
+
+    apply(defaults, attributes);
+  }
+}
+
+/// An edge between two nodes, identified by their index in [`Graph::nodes`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Edge<'src> {
+  pub attributes: Attributes<'src>,
+  pub head: usize,
+  pub head_port: Option<Port<'src>>,
+  pub tail: usize,
+  pub tail_port: Option<Port<'src>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Graph<'src> {
+  pub attributes: Attributes<'src>,
+  pub edges: Vec<Edge<'src>>,
+  pub id: Option<Id<'src>>,
+  pub kind: GraphKind,
+  pub nodes: Vec<Node<'src>>,
+  pub strict: bool,
+  pub subgraphs: Vec<Subgraph<'src>>,
+}
+
+impl<'src> Graph<'src> {
+  /// Look up a node by name.
+  #[must_use]
+  pub fn node(&self, name: &str) -> Option<&Node<'src>> {
+    self.nodes.iter().find(|node| node.id.decoded() == name)
+  }
+
+  /// Write the graph as Graphviz JSON, as [`to_json`] does.
+  #[must_use]
+  pub fn to_json(&self) -> String {
+    json::write(self)
+  }
+
+  /// Resolve `graph`, starting from `defaults` rather than no defaults, as
+  /// `dot -G`, `-N` and `-E` do.
+  ///
+  /// ```
+  /// use dotgraph::{Id, model};
+  ///
+  /// let graph = dotgraph::parse("digraph { a [shape=circle]; b }").unwrap();
+  ///
+  /// let mut defaults = model::Defaults::default();
+  ///
+  /// defaults.node.insert("shape".into(), Id::new("box"));
+  ///
+  /// let graph = model::Graph::with_defaults(&graph, defaults);
+  ///
+  /// assert_eq!(graph.node("a").unwrap().attributes["shape"].value, "circle");
+  /// assert_eq!(graph.node("b").unwrap().attributes["shape"].value, "box");
+  /// ```
+  #[must_use]
+  pub fn with_defaults(
+    graph: &ast::Graph<'src>,
+    defaults: Defaults<'src>,
+  ) -> Self {
+    let mut resolver = Resolver {
+      edges: HashMap::new(),
+      graph: Graph {
+        attributes: Attributes::new(),
+        edges: Vec::new(),
+        id: graph.id.clone(),
+        kind: graph.kind.clone(),
+        nodes: Vec::new(),
+        strict: graph.strict,
+        subgraphs: Vec::new(),
+      },
+      locals: BTreeMap::new(),
+      nodes: HashMap::new(),
+      path: Vec::new(),
+      scopes: vec![Scope {
+        effective: defaults,
+        local: Defaults::default(),
+      }],
+    };
+
+    resolver.statements(&graph.statements);
+
+    let root = resolver.scopes.pop().unwrap_or_default();
+
+    let mut graph = resolver.graph;
+
+    graph.attributes = root.effective.graph;
+
+    graph
+  }
+}
+
+impl<'src> From<&ast::Graph<'src>> for Graph<'src> {
+  fn from(graph: &ast::Graph<'src>) -> Self {
+    Self::with_defaults(graph, Defaults::default())
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Node<'src> {
+  pub attributes: Attributes<'src>,
+  pub id: Id<'src>,
+}
+
+struct Resolver<'src> {
+  edges: HashMap<(usize, usize), usize>,
+  graph: Graph<'src>,
+  locals: BTreeMap<Vec<usize>, Defaults<'src>>,
+  nodes: HashMap<Cow<'src, str>, usize>,
+  path: Vec<usize>,
+  scopes: Vec<Scope<'src>>,
+}
+
+impl<'src> Resolver<'src> {
+  /// Run `f` on the current subgraph and every subgraph enclosing it.
+  fn each_subgraph(&mut self, mut f: impl FnMut(&mut Subgraph<'src>)) {
+    let mut subgraphs = &mut self.graph.subgraphs;
+
+    for &index in &self.path {
+      let subgraph = &mut subgraphs[index];
+      f(subgraph);
+      subgraphs = &mut subgraph.subgraphs;
+    }
+  }
+
+  /// The key of an edge from `tail` to `head` in `edges`. The ends of an
+  /// undirected edge are put in order, so that it matches either direction.
+  fn edge_key(&self, tail: usize, head: usize) -> (usize, usize) {
+    if self.graph.kind == GraphKind::Graph {
+      (tail.min(head), tail.max(head))
+    } else {
+      (tail, head)
+    }
+  }
+
+  fn edge_statement(&mut self, statement: &EdgeStatement<'src>) {
+    let mut tail = self.endpoints(&statement.from);
+
+    for edge in &statement.edges {
+      let head = self.endpoints(&edge.target);
+
+      for (tail, tail_port) in &tail {
+        for (head, head_port) in &head {
+          let index = if let Some(index) = self.existing_edge(*tail, *head) {
+            let edge = &mut self.graph.edges[index];
+
+            apply(&mut edge.attributes, &statement.attributes);
+
+            if head_port.is_some() {
+              edge.head_port.clone_from(head_port);
+            }
+
+            if tail_port.is_some() {
+              edge.tail_port.clone_from(tail_port);
+            }
+
+            index
+          } else {
+            let mut attributes = self.scope().effective.edge.clone();
+
+            apply(&mut attributes, &statement.attributes);
+
+            self.graph.edges.push(Edge {
+              attributes,
+              head: *head,
+              head_port: head_port.clone(),
+              tail: *tail,
+              tail_port: tail_port.clone(),
+            });
+
+            let index = self.graph.edges.len() - 1;
+
+            self.edges.insert(self.edge_key(*tail, *head), index);
+
+            index
+          };
+
+          self.each_subgraph(|subgraph| {
+            if !subgraph.edges.contains(&index) {
+              subgraph.edges.push(index);
+            }
+          });
+        }
+      }
+
+      tail = head;
+    }
+  }
+
+  fn endpoints(
+    &mut self,
+    target: &EdgeTarget<'src>,
+  ) -> Vec<(usize, Option<Port<'src>>)> {
+    match target {
+      EdgeTarget::NodeId(node_id) => {
+        vec![(self.node(&node_id.id), node_id.port.clone())]
+      }
+      EdgeTarget::Subgraph(subgraph) => self
+        .subgraph(subgraph)
+        .nodes
+        .iter()
+        .map(|&node| (node, None))
+        .collect(),
+    }
+  }
+
+  /// In a strict graph, the edge that an edge from `tail` to `head` should be
+  /// merged into. Undirected edges match in either direction.
+  fn existing_edge(&self, tail: usize, head: usize) -> Option<usize> {
+    if !self.graph.strict {
+      return None;
+    }
+
+    self.edges.get(&self.edge_key(tail, head)).copied()
+  }
+
+  /// Return the index of the node named by `id`, creating it with the
+  /// current node defaults if it does not exist yet.
+  fn node(&mut self, id: &Id<'src>) -> usize {
+    let name = id.decoded();
+
+    let index = if let Some(&index) = self.nodes.get(&name) {
+      index
+    } else {
+      let attributes = self.scope().effective.node.clone();
+
+      self.graph.nodes.push(Node {
+        attributes,
+        id: id.clone(),
+      });
+
+      let index = self.graph.nodes.len() - 1;
+
+      self.nodes.insert(name, index);
+
+      index
+    };
+
+    self.each_subgraph(|subgraph| {
+      if !subgraph.nodes.contains(&index) {
+        subgraph.nodes.push(index);
+      }
+    });
+
+    index
+  }
+
+  fn scope(&mut self) -> &mut Scope<'src> {
+    self.scopes.last_mut().unwrap()
+  }
+
+  /// The subgraphs of the current graph or subgraph.
+  fn siblings(&mut self) -> &mut Vec<Subgraph<'src>> {
+    let mut subgraphs = &mut self.graph.subgraphs;
+
+    for &index in &self.path {
+      subgraphs = &mut subgraphs[index].subgraphs;
+    }
+
+    subgraphs
+  }
+
+  fn statements(&mut self, statements: &[Statement<'src>]) {
+    for statement in statements {
+      match statement {
+        Statement::Assign(key, value) => {
+          let attribute = Attribute {
+            key: key.clone(),
+            span: key.span.start..value.span.end,
+            value: Some(value.clone()),
+          };
+
+          self.update(&AttributeTarget::Graph, &[attribute]);
+        }
+        Statement::Attr(statement) => {
+          self.update(&statement.target, &statement.attributes);
+        }
+        Statement::Edge(statement) => self.edge_statement(statement),
+        Statement::Node(statement) => {
+          let index = self.node(&statement.id.id);
+          apply(
+            &mut self.graph.nodes[index].attributes,
+            &statement.attributes,
+          );
+        }
+        Statement::Subgraph(subgraph) => {
+          self.subgraph(subgraph);
+        }
+      }
+    }
+  }
+
+  /// Enter `subgraph`, resolve its statements and return the resolved
+  /// subgraph.
+  fn subgraph(&mut self, subgraph: &ast::Subgraph<'src>) -> &Subgraph<'src> {
+    let siblings = self.siblings();
+
+    let existing = subgraph.id.as_ref().and_then(|id| {
+      siblings.iter().position(|sibling| {
+        sibling
+          .id
+          .as_ref()
+          .is_some_and(|other| other.decoded() == id.decoded())
+      })
+    });
+
+    let index = existing.unwrap_or_else(|| {
+      siblings.push(Subgraph {
+        attributes: Attributes::new(),
+        edges: Vec::new(),
+        id: subgraph.id.clone(),
+        nodes: Vec::new(),
+        subgraphs: Vec::new(),
+      });
+
+      siblings.len() - 1
+    });
+
+    self.path.push(index);
+
+    let local = self.locals.get(&self.path).cloned().unwrap_or_default();
+
+    let mut effective = self.scope().effective.clone();
+
+    effective.extend(&local);
+
+    self.scopes.push(Scope { effective, local });
+
+    self.statements(&subgraph.statements);
+
+    let scope = self.scopes.pop().unwrap();
+
+    self.locals.insert(self.path.clone(), scope.local);
+
+    self.path.pop();
+
+    let subgraph = &mut self.siblings()[index];
+
+    subgraph.attributes = scope.effective.graph;
+
+    subgraph
+  }
+
+  fn update(
+    &mut self,
+    target: &AttributeTarget,
+    attributes: &[Attribute<'src>],
+  ) {
+    let scope = self.scope();
+    scope.effective.set(target, attributes);
+    scope.local.set(target, attributes);
+  }
+}
+
+#[derive(Clone, Debug, Default)]
+struct Scope<'src> {
+  effective: Defaults<'src>,
+  local: Defaults<'src>,
+}
+
+/// A subgraph, with the nodes and edges declared inside it or inside any
+/// subgraph nested in it, as indices into [`Graph::nodes`] and
+/// [`Graph::edges`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Subgraph<'src> {
+  pub attributes: Attributes<'src>,
+  pub edges: Vec<usize>,
+  pub id: Option<Id<'src>>,
+  pub nodes: Vec<usize>,
+  pub subgraphs: Vec<Subgraph<'src>>,
+}
+
+/// Apply `attributes` to `map`. An attribute without a value, such as the
+/// `bold` in `[bold]`, is set to `true`, as Graphviz does.
+fn apply<'src>(map: &mut Attributes<'src>, attributes: &[Attribute<'src>]) {
+  for attribute in attributes {
+    let value = attribute.value.clone().unwrap_or(Id {
+      kind: IdKind::Ident,
+      span: attribute.key.span.clone(),
+      value: Cow::Borrowed("true"),
+    });
+
+    map.insert(attribute.key.decoded().into_owned(), value);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn attributes<'a>(attributes: &'a Attributes) -> Vec<(&'a str, &'a str)> {
+    attributes
+      .iter()
+      .map(|(key, value)| (key.as_str(), &*value.value))
+      .collect()
+  }
+
+  #[test]
+  fn defaults() {
+    let overrides = parse(
+      "graph { graph [rankdir=LR, label=x] node [shape=box, color=red] edge [style=dashed] }",
+    )
+    .unwrap();
+
+    let mut defaults = Defaults::default();
+
+    for statement in &overrides.statements {
+      if let Statement::Attr(statement) = statement {
+        defaults.set(&statement.target, &statement.attributes);
+      }
+    }
+
+    let src = "digraph { label=y; a [color=blue]; subgraph s { node [shape=oval]; b } a -> b [style=bold]; b -> c }";
+
+    let graph = Graph::with_defaults(&parse(src).unwrap(), defaults);
+
+    assert_eq!(
+      attributes(&graph.attributes),
+      vec![("label", "y"), ("rankdir", "LR")],
+    );
+
+    assert_eq!(
+      attributes(&graph.subgraphs[0].attributes),
+      vec![("label", "y"), ("rankdir", "LR")],
+    );
+
+    assert_eq!(
+      attributes(&graph.node("a").unwrap().attributes),
+      vec![("color", "blue"), ("shape", "box")],
+    );
+
+    assert_eq!(
+      attributes(&graph.node("b").unwrap().attributes),
+      vec![("color", "red"), ("shape", "oval")],
+    );
+
+    assert_eq!(
+      attributes(&graph.edges[0].attributes),
+      vec![("style", "bold")]
+    );
+
+    assert_eq!(
+      attributes(&graph.edges[1].attributes),
+      vec![("style", "dashed")],
+    );
+  }
+
+  #[test]
+  fn edge_chains() {
+    let graph = resolve("digraph { a -> {b c} -> d }");
+
+    assert_eq!(
+      edges(&graph),
+      vec![("a", "b"), ("a", "c"), ("b", "d"), ("c", "d")],
+    );
+  }
+
+  #[test]
+  fn edge_defaults() {
+    let graph = resolve(
+      "digraph { a -> b; edge [color=red, style=bold] b -> c [color=blue] }",
+    );
+
+    assert_eq!(attributes(&graph.edges[0].attributes), vec![]);
+
+    assert_eq!(
+      attributes(&graph.edges[1].attributes),
+      vec![("color", "blue"), ("style", "bold")],
+    );
+  }
+
+  #[test]
+  fn edge_ports() {
+    let graph = resolve("digraph { a:p1:n -> b:p2 }");
+
+    let edge = &graph.edges[0];
+
+    assert_matches!(
+      (&edge.tail_port, &edge.head_port),
+      (
+        Some(Port {
+          id: tail,
+          compass: Some(compass),
+          ..
+        }),
+        Some(Port {
+          id: head,
+          compass: None,
+          ..
+        }),
+      ) if tail.value == "p1" && compass.value == "n" && head.value == "p2",
+    );
+  }
+
+  fn edges<'a>(graph: &'a Graph) -> Vec<(&'a str, &'a str)> {
+    graph
+      .edges
+      .iter()
+      .map(|edge| {
+        (
+          &*graph.nodes[edge.tail].id.value,
+          &*graph.nodes[edge.head].id.value,
+        )
+      })
+      .collect()
+  }
+
+  #[test]
+  fn graph_attributes() {
+    let graph = resolve(
+      "digraph { label=x; subgraph cluster_0 { color=red } graph [rankdir=LR] }",
+    );
+
+    assert_eq!(
+      attributes(&graph.attributes),
+      vec![("label", "x"), ("rankdir", "LR")],
+    );
+
+    assert_eq!(
+      attributes(&graph.subgraphs[0].attributes),
+      vec![("color", "red"), ("label", "x")],
+    );
+  }
+
+  #[test]
+  fn multi_edges() {
+    let graph = resolve("digraph { a -> b; a -> b }");
+
+    assert_eq!(edges(&graph), vec![("a", "b"), ("a", "b")]);
+  }
+
+  #[test]
+  fn node_defaults() {
+    let graph = resolve(
+      "digraph { a; node [shape=box, color=red]; b; c [color=blue]; a }",
+    );
+
+    assert_eq!(attributes(&graph.node("a").unwrap().attributes), vec![]);
+
+    assert_eq!(
+      attributes(&graph.node("b").unwrap().attributes),
+      vec![("color", "red"), ("shape", "box")],
+    );
+
+    assert_eq!(
+      attributes(&graph.node("c").unwrap().attributes),
+      vec![("color", "blue"), ("shape", "box")],
+    );
+  }
+
+  #[test]
+  fn nodes_are_unique() {
+    let graph = resolve(
+      "digraph { a; b -> a; \"a\" [color=red] \"\" + \"a\" [\"sha\" + \"pe\"=box] }",
+    );
+
+    assert_eq!(
+      graph
+        .nodes
+        .iter()
+        .map(|node| &*node.id.value)
+        .collect::<Vec<_>>(),
+      vec!["a", "b"],
+    );
+
+    assert_eq!(
+      attributes(&graph.node("a").unwrap().attributes),
+      vec![("color", "red"), ("shape", "box")],
+    );
+  }
+
+  #[test]
+  fn reopened_subgraph() {
+    let graph = resolve(
+      "digraph { subgraph s { node [shape=box] a } subgraph s { b } c }",
+    );
+
+    assert_eq!(graph.subgraphs.len(), 1);
+    assert_eq!(graph.subgraphs[0].nodes, vec![0, 1]);
+
+    assert_eq!(
+      attributes(&graph.node("b").unwrap().attributes),
+      vec![("shape", "box")],
+    );
+
+    assert_eq!(attributes(&graph.node("c").unwrap().attributes), vec![]);
+  }
+
+  fn resolve(src: &str) -> Graph<'_> {
+    Graph::from(&parse(src).unwrap())
+  }
+
+  #[cfg(feature = "serde")]
+  #[test]
+  fn serde() {
+    let graph = resolve(
+      "strict digraph { edge [color=red] a:p -> b; subgraph cluster_0 { label=x; c -> a } }",
+    );
+
+    let json = serde_json::to_value(&graph).unwrap();
+
+    assert_eq!(json["edges"][1]["attributes"]["color"]["value"], "red");
+    assert_eq!(json["edges"][0]["tail_port"]["id"]["value"], "p");
+    assert_eq!(json["subgraphs"][0]["nodes"], serde_json::json!([2, 0]));
+
+    assert_eq!(
+      serde_json::from_value::<Graph<'static>>(json).unwrap(),
+      graph
+    );
+  }
+
+  #[test]
+  fn strict_directed() {
+    let graph = resolve(
+      "strict digraph { a -> b [color=red]; a -> b [style=bold, color=blue]; b -> a }",
+    );
+
+    assert_eq!(edges(&graph), vec![("a", "b"), ("b", "a")]);
+
+    assert_eq!(
+      attributes(&graph.edges[0].attributes),
+      vec![("color", "blue"), ("style", "bold")],
+    );
+  }
+
+  #[test]
+  fn strict_ports() {
+    let graph = resolve("strict digraph { a:p1 -> b; a -> b:p2 }");
+
+    assert_eq!(graph.edges.len(), 1);
+
+    assert_matches!(
+      (&graph.edges[0].tail_port, &graph.edges[0].head_port),
+      (
+        Some(Port { id: tail, .. }),
+        Some(Port { id: head, .. })
+      ) if tail.value == "p1" && head.value == "p2",
+    );
+  }
+
+  #[test]
+  fn strict_self_loops() {
+    let graph = resolve("strict digraph { a -> a; a -> a [color=red] }");
+
+    assert_eq!(edges(&graph), vec![("a", "a")]);
+
+    assert_eq!(
+      attributes(&graph.edges[0].attributes),
+      vec![("color", "red")],
+    );
+  }
+
+  #[test]
+  fn strict_subgraph_membership() {
+    let graph =
+      resolve("strict digraph { a -> b; subgraph s { b -> a; a -> b } }");
+
+    assert_eq!(edges(&graph), vec![("a", "b"), ("b", "a")]);
+    assert_eq!(graph.subgraphs[0].edges, vec![1, 0]);
+  }
+
+  #[test]
+  fn strict_undirected() {
+    let graph = resolve(
+      "strict graph { edge [color=red] a -- b; edge [color=blue] b -- a [style=bold]; a -- {b c} }",
+    );
+
+    assert_eq!(edges(&graph), vec![("a", "b"), ("a", "c")]);
+
+    assert_eq!(
+      attributes(&graph.edges[0].attributes),
+      vec![("color", "red"), ("style", "bold")],
+    );
+  }
+
+  #[test]
+  fn subgraph_defaults() {
+    let graph = resolve(
+      "digraph { node [shape=box] subgraph s { node [color=red] a } b }",
+    );
+
+    assert_eq!(
+      attributes(&graph.node("a").unwrap().attributes),
+      vec![("color", "red"), ("shape", "box")],
+    );
+
+    assert_eq!(
+      attributes(&graph.node("b").unwrap().attributes),
+      vec![("shape", "box")],
+    );
+  }
+
+  #[test]
+  fn subgraph_membership() {
+    let graph = resolve(
+      "digraph { x; subgraph outer { a -> b; subgraph inner { c -> x } } }",
+    );
+
+    let outer = &graph.subgraphs[0];
+
+    assert_eq!(outer.nodes, vec![1, 2, 3, 0]);
+    assert_eq!(outer.edges, vec![0, 1]);
+
+    let inner = &outer.subgraphs[0];
+
+    assert_eq!(inner.nodes, vec![3, 0]);
+    assert_eq!(inner.edges, vec![1]);
+  }
+
+  #[test]
+  fn valueless_attributes() {
+    let graph = resolve("digraph { a [bold] }");
+
+    assert_eq!(
+      attributes(&graph.node("a").unwrap().attributes),
+      vec![("bold", "true")],
+    );
+  }
+}
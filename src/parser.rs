@@ -1,70 +1,204 @@
 use super::*;
 
-#[derive(Debug)]
-pub struct ParseError {
-  pub message: String,
-  pub span: Range<usize>,
+/// Parse `tokens`, which must contain exactly one graph, reporting the end of
+/// input at `end`.
+///
+/// The parser recovers from errors in statements and attribute lists, so a
+/// graph may be returned together with errors, with the statements and
+/// attributes that failed to parse left out.
+fn graph<'src>(
+  tokens: &[Spanned<'src>],
+  end: usize,
+) -> (Option<Graph<'src>>, Vec<ParseError>) {
+  let eoi: Span = (end..end).into();
+
+  let (graph, errors) = parser()
+    .parse(tokens.map(eoi, |(t, s)| (t, s)))
+    .into_output_errors();
+
+  // Blocks and attribute lists that are still open at the end of the input,
+  // innermost last.
+  let mut open = Vec::new();
+
+  for (token, span) in tokens {
+    match token {
+      Token::OpenBrace | Token::OpenBracket => open.push((token, span)),
+      Token::CloseBrace | Token::CloseBracket => {
+        open.pop();
+      }
+      _ => {}
+    }
+  }
+
+  let errors = errors
+    .into_iter()
+    .map(|error| {
+      for (close, kind, name) in [
+        (Token::CloseBrace, ErrorKind::UnclosedBrace, "brace"),
+        (Token::CloseBracket, ErrorKind::UnclosedBracket, "bracket"),
+      ] {
+        if !error.expected_at_end(&close) {
+          continue;
+        }
+
+        let opening = open.iter().rposition(|(token, _)| {
+          matches!(
+            (token, &close),
+            (Token::OpenBrace, Token::CloseBrace)
+              | (Token::OpenBracket, Token::CloseBracket)
+          )
+        });
+
+        if let Some(opening) = opening {
+          let (_, span) = open.remove(opening);
+
+          return ParseError::new(
+            kind,
+            format!("unclosed {name}"),
+            error.span(),
+          )
+          .with_label(format!("this {name} is never closed"), span.into_range())
+          .with_help(format!("add a closing `{close}`"));
+        }
+      }
+
+      error.into_parse_error(ErrorKind::UnexpectedToken)
+    })
+    .collect();
+
+  (graph, errors)
 }
 
 pub(crate) fn parse(src: &str) -> Result<Graph<'_>, Vec<ParseError>> {
-  let tokens = lexer::lex(src).into_result().map_err(|errors| {
-    errors
-      .into_iter()
-      .map(|error| ParseError {
-        message: error.to_string(),
-        span: error.span().into_range(),
-      })
-      .collect::<Vec<_>>()
-  })?;
+  result(parse_partial(src))
+}
 
-  let eoi: Span = (src.len()..src.len()).into();
+pub(crate) fn parse_all(src: &str) -> Vec<Result<Graph<'_>, Vec<ParseError>>> {
+  let tokens = match tokens(src) {
+    Ok(tokens) => tokens,
+    Err(errors) => return vec![Err(errors)],
+  };
 
-  parser()
-    .parse(tokens.as_slice().map(eoi, |(t, s)| (t, s)))
-    .into_result()
-    .map_err(|errors| {
-      errors
-        .into_iter()
-        .map(|error| ParseError {
-          message: error.to_string(),
-          span: error.span().into_range(),
-        })
-        .collect::<Vec<_>>()
-    })
+  let mut graphs = Vec::new();
+
+  let mut depth = 0usize;
+  let mut start = 0;
+
+  for (index, (token, span)) in tokens.iter().enumerate() {
+    match token {
+      Token::OpenBrace => depth += 1,
+      Token::CloseBrace if depth > 1 => depth -= 1,
+      Token::CloseBrace if depth == 1 => {
+        graphs.push(result(graph(&tokens[start..=index], span.end)));
+        depth = 0;
+        start = index + 1;
+      }
+      _ => {}
+    }
+  }
+
+  if start < tokens.len() {
+    graphs.push(result(graph(&tokens[start..], src.len())));
+  }
+
+  graphs
 }
 
-fn parser<'tokens, 'src: 'tokens, I>()
--> impl Parser<'tokens, I, Graph<'src>, extra::Err<Rich<'tokens, Token<'src>, Span>>>
+pub(crate) fn parse_partial(src: &str) -> (Option<Graph<'_>>, Vec<ParseError>) {
+  match tokens(src) {
+    Ok(tokens) => graph(&tokens, src.len()),
+    Err(errors) => (None, errors),
+  }
+}
+
+fn parser<'tokens, 'src: 'tokens, I>() -> impl Parser<
+  'tokens,
+  I,
+  Graph<'src>,
+  extra::Err<SyntaxError<'tokens, Token<'src>>>,
+>
 where
   I: ValueInput<'tokens, Token = Token<'src>, Span = Span>,
 {
   let id = select! {
-    Token::Ident(s) => Id::Ident(s),
-    Token::String(s) => Id::String(s),
-    Token::HtmlString(s) => Id::HtmlString(s),
-    Token::Number(s) => Id::Number(s),
-  };
+    Token::Ident(value) => (IdKind::Ident, value),
+    Token::String(value) => (IdKind::String, value),
+    Token::HtmlString(value) => (IdKind::HtmlString, value),
+    Token::Number(value) => (IdKind::Number, value),
+  }
+  .map_with(|(kind, value), e| Id {
+    kind,
+    span: span(e),
+    value: Cow::Borrowed(value),
+  });
 
   let port = just(Token::Colon)
     .ignore_then(id)
     .then(just(Token::Colon).ignore_then(id).or_not())
-    .map(|(id, compass)| Port { compass, id });
+    .map_with(|(id, compass), e| Port {
+      compass,
+      id,
+      span: span(e),
+    });
 
-  let node_id = id.then(port.or_not()).map(|(id, port)| NodeId { id, port });
+  let node_id = id.then(port.or_not()).map_with(|(id, port), e| NodeId {
+    id,
+    port,
+    span: span(e),
+  });
 
-  let attribute = id
-    .then(just(Token::Equals).ignore_then(id).or_not())
-    .then_ignore(just(Token::Semicolon).or(just(Token::Comma)).or_not())
-    .map(|(key, value)| Attribute { key, value });
+  let separator = just(Token::Semicolon).or(just(Token::Comma));
+
+  // An attribute that fails to parse is skipped up to the next separator, the
+  // end of the list, or the end of the enclosing block. The lookahead makes
+  // sure recovery never starts at a token it would stop at without consuming
+  // anything.
+  let attribute = any()
+    .and_is(just(Token::CloseBracket).not())
+    .and_is(just(Token::CloseBrace).not())
+    .rewind()
+    .ignore_then(
+      id.then(just(Token::Equals).ignore_then(id).or_not())
+        .map_with(|(key, value), e| {
+          Some(Attribute {
+            key,
+            span: span(e),
+            value,
+          })
+        })
+        .then_ignore(separator.clone().or_not())
+        .recover_with(via_parser(
+          any()
+            .and_is(separator.clone().not())
+            .and_is(just(Token::CloseBracket).not())
+            .and_is(just(Token::CloseBrace).not())
+            .repeated()
+            .then(separator.or_not())
+            .to(None),
+        )),
+    );
 
   let attribute_list = just(Token::OpenBracket)
     .ignore_then(attribute.repeated().collect::<Vec<_>>())
     .then_ignore(just(Token::CloseBracket))
+    .map(|attributes| attributes.into_iter().flatten().collect::<Vec<_>>())
+    .recover_with(via_parser(nested_delimiters(
+      Token::OpenBracket,
+      Token::CloseBracket,
+      [(Token::OpenBrace, Token::CloseBrace)],
+      |_| Vec::new(),
+    )))
     .repeated()
     .at_least(1)
     .collect::<Vec<Vec<Attribute>>>()
     .map(|lists| lists.into_iter().flatten().collect::<Vec<_>>());
 
+  // A missing closing brace at the end of the input is reported, but the
+  // block is still closed, so that a graph that is being typed can be parsed.
+  let close_brace = just(Token::CloseBrace)
+    .ignored()
+    .recover_with(via_parser(end()));
+
   let edge_operation = select! {
     Token::Arrow => EdgeOperation::Arrow,
     Token::DashDash => EdgeOperation::DashDash,
@@ -77,10 +211,11 @@ where
       .then(
         just(Token::OpenBrace)
           .ignore_then(statement_list)
-          .then_ignore(just(Token::CloseBrace)),
+          .then_ignore(close_brace.clone()),
       )
-      .map(|(header, statements)| Subgraph {
+      .map_with(|(header, statements), e| Subgraph {
         id: header.flatten(),
+        span: span(e),
         statements,
       });
 
@@ -91,21 +226,15 @@ where
 
     let edge_rhs = edge_operation
       .then(edge_target.clone())
+      .map_with(|(operation, target), e| Edge {
+        operation,
+        span: span(e),
+        target,
+      })
       .repeated()
       .at_least(1)
       .collect::<Vec<_>>();
 
-    let edge_statement = edge_target
-      .then(edge_rhs)
-      .then(attribute_list.clone().or_not())
-      .map(|((from, edges), attrs)| {
-        Statement::Edge(EdgeStatement {
-          attributes: attrs.unwrap_or_default(),
-          edges,
-          from,
-        })
-      });
-
     let attribute_target = select! {
       Token::Graph => AttributeTarget::Graph,
       Token::Node => AttributeTarget::Node,
@@ -114,9 +243,10 @@ where
 
     let attribute_statement = attribute_target
       .then(attribute_list.clone())
-      .map(|(target, attrs)| {
+      .map_with(|(target, attrs), e| {
         Statement::Attr(AttributeStatement {
           attributes: attrs,
+          span: span(e),
           target,
         })
       });
@@ -126,26 +256,68 @@ where
       .then(id)
       .map(|(key, value)| Statement::Assign(key, value));
 
-    let node_statement =
-      node_id.then(attribute_list.or_not()).map(|(id, attrs)| {
-        Statement::Node(NodeStatement {
-          attributes: attrs.unwrap_or_default(),
-          id,
+    // Node, edge and subgraph statements all start with an edge target, and
+    // are parsed together so that a subgraph is only parsed once, which keeps
+    // errors recovered inside it from being reported twice.
+    let target_statement = edge_target
+      .then(edge_rhs.or_not())
+      .then(attribute_list.or_not())
+      .try_map(|((from, edges), attributes), span: Span| {
+        Ok(match (from, edges) {
+          (from, Some(edges)) => Statement::Edge(EdgeStatement {
+            attributes: attributes.unwrap_or_default(),
+            edges,
+            from,
+            span: span.into_range(),
+          }),
+          (EdgeTarget::NodeId(id), None) => Statement::Node(NodeStatement {
+            attributes: attributes.unwrap_or_default(),
+            id,
+            span: span.into_range(),
+          }),
+          (EdgeTarget::Subgraph(_), None) if attributes.is_some() => {
+            return Err(SyntaxError::custom(
+              ErrorKind::AttributesAfterSubgraph,
+              span,
+              "attributes are not allowed after a subgraph",
+            ));
+          }
+          (EdgeTarget::Subgraph(subgraph), None) => {
+            Statement::Subgraph(subgraph)
+          }
         })
       });
 
-    let statement = choice((
-      attribute_statement,
-      edge_statement,
-      assign,
-      subgraph.map(Statement::Subgraph),
-      node_statement,
-    ));
+    let statement = choice((attribute_statement, assign, target_statement));
 
-    statement
-      .then_ignore(just(Token::Semicolon).or_not())
+    // A statement that fails to parse is skipped up to the next semicolon, or
+    // the end of the enclosing block. Blocks and attribute lists inside the
+    // statement are skipped as a whole.
+    let skip = nested_delimiters(
+      Token::OpenBrace,
+      Token::CloseBrace,
+      [(Token::OpenBracket, Token::CloseBracket)],
+      |_| (),
+    )
+    .or(any().ignored());
+
+    let until = just(Token::Semicolon)
+      .ignored()
+      .or(just(Token::CloseBrace).ignored().rewind())
+      .or(end());
+
+    any()
+      .and_is(just(Token::CloseBrace).not())
+      .rewind()
+      .ignore_then(
+        statement
+          .map(Some)
+          .then_ignore(just(Token::Semicolon).or_not())
+          .recover_with(skip_until(skip, until, || None)),
+      )
       .repeated()
       .collect::<Vec<_>>()
+      .map(|statements| statements.into_iter().flatten().collect::<Vec<_>>())
   });
 
   let strict = just(Token::Strict).or_not().map(|s| s.is_some());
@@ -161,15 +333,52 @@ where
     .then(
       just(Token::OpenBrace)
         .ignore_then(statement_list)
-        .then_ignore(just(Token::CloseBrace)),
+        .then_ignore(close_brace),
     )
-    .then_ignore(end())
-    .map(|(((strict, kind), id), statements)| Graph {
+    .map_with(|(((strict, kind), id), statements), e| Graph {
       id,
       kind,
+      span: span(e),
       statements,
       strict,
     })
+    .then_ignore(end())
+}
+
+fn result(
+  (graph, errors): (Option<Graph<'_>>, Vec<ParseError>),
+) -> Result<Graph<'_>, Vec<ParseError>> {
+  match graph {
+    Some(graph) if errors.is_empty() => Ok(graph),
+    _ => Err(errors),
+  }
+}
+
+fn span<'tokens, 'src: 'tokens, I, E>(
+  e: &mut MapExtra<'tokens, '_, I, E>,
+) -> Range<usize>
+where
+  I: ValueInput<'tokens, Token = Token<'src>, Span = Span>,
+  E: ParserExtra<'tokens, I>,
+{
+  e.span().into_range()
+}
+
+/// Lex `src`, dropping comments, which the parser does not see.
+fn tokens(src: &str) -> Result<Vec<Spanned<'_>>, Vec<ParseError>> {
+  Ok(
+    lexer::lex(src)
+      .into_result()
+      .map_err(|errors| {
+        errors
+          .into_iter()
+          .map(|error| error.into_parse_error(ErrorKind::UnexpectedCharacter))
+          .collect::<Vec<_>>()
+      })?
+      .into_iter()
+      .filter(|(token, _)| !matches!(token, Token::Comment(_)))
+      .collect(),
+  )
 }
 
 #[cfg(test)]
@@ -182,7 +391,7 @@ mod tests {
 
     assert_matches!(
       &ast.statements[..],
-      [Statement::Subgraph(Subgraph { id: None, statements })] if statements.len() == 1,
+      [Statement::Subgraph(Subgraph { id: None, statements, .. })] if statements.len() == 1,
     );
   }
 
@@ -192,7 +401,10 @@ mod tests {
 
     assert_eq!(
       ast.statements,
-      vec![Statement::Assign(Id::Ident("label"), Id::String("hello"),)],
+      vec![Statement::Assign(
+        id(IdKind::Ident, 10..15, "label"),
+        id(IdKind::String, 18..25, "hello"),
+      )],
     );
   }
 
@@ -204,9 +416,11 @@ mod tests {
       ast.statements,
       vec![Statement::Attr(AttributeStatement {
         attributes: vec![Attribute {
-          key: Id::Ident("shape"),
-          value: Some(Id::Ident("circle")),
+          key: id(IdKind::Ident, 16..21, "shape"),
+          span: 16..28,
+          value: Some(id(IdKind::Ident, 22..28, "circle")),
         }],
+        span: 10..29,
         target: AttributeTarget::Node,
       })],
     );
@@ -230,17 +444,21 @@ mod tests {
       ast.statements,
       vec![Statement::Edge(EdgeStatement {
         attributes: vec![],
-        edges: vec![(
-          EdgeOperation::Arrow,
-          EdgeTarget::NodeId(NodeId {
-            id: Id::Ident("b"),
+        edges: vec![Edge {
+          operation: EdgeOperation::Arrow,
+          span: 12..16,
+          target: EdgeTarget::NodeId(NodeId {
+            id: id(IdKind::Ident, 15..16, "b"),
             port: None,
+            span: 15..16,
           }),
-        )],
+        }],
         from: EdgeTarget::NodeId(NodeId {
-          id: Id::Ident("a"),
+          id: id(IdKind::Ident, 10..11, "a"),
           port: None,
+          span: 10..11,
         }),
+        span: 10..16,
       })],
     );
   }
@@ -254,6 +472,7 @@ mod tests {
       Graph {
         id: None,
         kind: GraphKind::Digraph,
+        span: 0..10,
         statements: vec![],
         strict: false,
       },
@@ -269,17 +488,80 @@ mod tests {
       Graph {
         id: None,
         kind: GraphKind::Graph,
+        span: 0..8,
         statements: vec![],
         strict: false,
       },
     );
   }
 
+  #[test]
+  fn error_kinds() {
+    #[track_caller]
+    fn case(src: &str, expected: &[ErrorKind]) {
+      assert_eq!(
+        super::parse_partial(src)
+          .1
+          .iter()
+          .map(|error| error.kind)
+          .collect::<Vec<_>>(),
+        expected,
+      );
+    }
+
+    case("digraph { a -> ; }", &[ErrorKind::UnexpectedToken]);
+    case(
+      "digraph { a -> ",
+      &[ErrorKind::UnexpectedEndOfInput, ErrorKind::UnclosedBrace],
+    );
+    case(
+      "digraph { {a} [color=red] }",
+      &[ErrorKind::AttributesAfterSubgraph],
+    );
+    case("digraph { a @ }", &[ErrorKind::UnexpectedCharacter]);
+    case("digraph { a [label=\"b }", &[ErrorKind::UnclosedString]);
+    case("digraph { a [label=<b }", &[ErrorKind::UnclosedHtmlString]);
+    case("digraph { a /* b }", &[ErrorKind::UnclosedComment]);
+    case(
+      "digraph { a\n  # b\n}",
+      &[ErrorKind::MisplacedPreprocessorLine],
+    );
+  }
+
+  fn id(kind: IdKind, span: Range<usize>, value: &str) -> Id<'_> {
+    Id {
+      kind,
+      span,
+      value: Cow::Borrowed(value),
+    }
+  }
+
+  #[test]
+  fn id_kinds() {
+    let ast = parse("digraph { a -> \"b\" -> <c> -> 1.5 }");
+
+    let Statement::Edge(EdgeStatement { edges, .. }) = &ast.statements[0]
+    else {
+      panic!("expected edge statement");
+    };
+
+    assert_eq!(
+      edges
+        .iter()
+        .map(|edge| match &edge.target {
+          EdgeTarget::NodeId(node_id) => node_id.id.kind,
+          EdgeTarget::Subgraph(_) => panic!("expected node id"),
+        })
+        .collect::<Vec<_>>(),
+      vec![IdKind::String, IdKind::HtmlString, IdKind::Number],
+    );
+  }
+
   #[test]
   fn named_graph() {
     let ast = parse("digraph G {}");
 
-    assert_eq!(ast.id, Some(Id::Ident("G")));
+    assert_eq!(ast.id, Some(id(IdKind::Ident, 8..9, "G")));
   }
 
   #[test]
@@ -291,9 +573,11 @@ mod tests {
       vec![Statement::Node(NodeStatement {
         attributes: vec![],
         id: NodeId {
-          id: Id::Ident("a"),
+          id: id(IdKind::Ident, 10..11, "a"),
           port: None,
+          span: 10..11,
         },
+        span: 10..11,
       })],
     );
   }
@@ -307,18 +591,22 @@ mod tests {
       vec![Statement::Node(NodeStatement {
         attributes: vec![
           Attribute {
-            key: Id::Ident("color"),
-            value: Some(Id::Ident("red")),
+            key: id(IdKind::Ident, 13..18, "color"),
+            span: 13..22,
+            value: Some(id(IdKind::Ident, 19..22, "red")),
           },
           Attribute {
-            key: Id::Ident("shape"),
-            value: Some(Id::Ident("circle")),
+            key: id(IdKind::Ident, 24..29, "shape"),
+            span: 24..36,
+            value: Some(id(IdKind::Ident, 30..36, "circle")),
           },
         ],
         id: NodeId {
-          id: Id::Ident("a"),
+          id: id(IdKind::Ident, 10..11, "a"),
           port: None,
+          span: 10..11,
         },
+        span: 10..37,
       })],
     );
   }
@@ -327,6 +615,73 @@ mod tests {
     super::parse(input).expect("parsing failed")
   }
 
+  #[test]
+  fn parse_all() {
+    let graphs = super::parse_all(
+      "graph { a -- b } /* between */ digraph G { subgraph { c } }\ngraph {}",
+    );
+
+    assert_eq!(graphs.len(), 3);
+
+    let graphs = graphs.into_iter().map(Result::unwrap).collect::<Vec<_>>();
+
+    assert_eq!(graphs[0].span, 0..16);
+    assert_eq!(graphs[1].id.as_ref().unwrap().value, "G");
+    assert_eq!(graphs[2].kind, GraphKind::Graph);
+  }
+
+  #[test]
+  fn parse_all_empty() {
+    assert!(super::parse_all("").is_empty());
+    assert!(super::parse_all(" // nothing here\n").is_empty());
+  }
+
+  #[test]
+  fn parse_all_errors() {
+    let src = "graph { a -- } digraph { b } graph { c";
+
+    let graphs = super::parse_all(src);
+
+    assert_eq!(graphs.len(), 3);
+
+    assert_eq!(graphs[0].as_ref().unwrap_err()[0].span, 13..14);
+    assert!(graphs[1].is_ok());
+    assert_eq!(graphs[2].as_ref().unwrap_err()[0].span, 38..38);
+
+    assert_eq!(super::parse_all("graph { @ }").len(), 1);
+  }
+
+  #[test]
+  fn parse_partial() {
+    let (graph, errors) = super::parse_partial("digraph G { a -> b; c -> ; d");
+
+    let graph = graph.unwrap();
+
+    assert_eq!(graph.id.unwrap().value, "G");
+    assert_eq!(graph.statements.len(), 3);
+    assert_eq!(errors.len(), 2);
+
+    let (graph, errors) = super::parse_partial("digraph { a -> b }");
+
+    assert!(graph.is_some());
+    assert!(errors.is_empty());
+
+    let (graph, errors) = super::parse_partial("digraph { @ }");
+
+    assert!(graph.is_none());
+    assert_eq!(errors.len(), 1);
+
+    let (graph, errors) = super::parse_partial("graph graph");
+
+    assert!(graph.is_none());
+    assert_eq!(errors.len(), 1);
+  }
+
+  #[test]
+  fn parse_rejects_multiple_graphs() {
+    assert!(super::parse("graph {} graph {}").is_err());
+  }
+
   #[test]
   fn port() {
     let ast = parse("digraph { a:p1 -> b:p2 }");
@@ -337,12 +692,13 @@ mod tests {
         from: EdgeTarget::NodeId(NodeId {
           port: Some(Port {
             compass: None,
-            id: Id::Ident("p1")
+            id,
+            span,
           }),
           ..
         }),
         ..
-      })],
+      })] if id.value == "p1" && *span == (11..14),
     );
   }
 
@@ -355,16 +711,84 @@ mod tests {
       [Statement::Edge(EdgeStatement {
         from: EdgeTarget::NodeId(NodeId {
           port: Some(Port {
-            compass: Some(Id::Ident("n")),
-            id: Id::Ident("p1")
+            compass: Some(compass),
+            id,
+            ..
           }),
           ..
         }),
         ..
-      })],
+      })] if compass.value == "n" && id.value == "p1",
+    );
+  }
+
+  #[track_caller]
+  fn recover(src: &str) -> (Graph<'_>, Vec<&str>) {
+    let (graph, errors) = graph(&tokens(src).unwrap(), src.len());
+
+    (
+      graph.expect("recovery failed"),
+      errors
+        .iter()
+        .map(|error| &src[error.span.clone()])
+        .collect(),
+    )
+  }
+
+  #[test]
+  fn recover_attribute() {
+    let (graph, errors) =
+      recover("digraph { a [color=red, = , shape=box] b [x=] }");
+
+    assert_eq!(errors, vec!["=", "]"]);
+
+    assert_matches!(
+      &graph.statements[..],
+      [
+        Statement::Node(NodeStatement { attributes: a, .. }),
+        Statement::Node(NodeStatement { attributes: b, .. }),
+      ] if a.len() == 2 && b.len() == 1,
+    );
+  }
+
+  #[test]
+  fn recover_statements() {
+    let (graph, errors) = recover("digraph { a -> ; b; c -> -> d; e [x=1 }");
+
+    assert_eq!(errors, vec![";", "->", "}"]);
+
+    assert_eq!(
+      graph
+        .statements
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>(),
+      vec!["a", "b", "c", "e"],
+    );
+  }
+
+  #[test]
+  fn recover_subgraph() {
+    let (graph, errors) = recover("digraph { subgraph s { a -> } b }");
+
+    assert_eq!(errors, vec!["}"]);
+
+    assert_matches!(
+      &graph.statements[..],
+      [Statement::Subgraph(Subgraph { statements, .. }), Statement::Node(_)]
+        if statements.len() == 1,
     );
   }
 
+  #[test]
+  fn recover_unclosed_brace() {
+    let (graph, errors) = recover("digraph { a -> b; subgraph { c");
+
+    assert_eq!(errors, vec!["", ""]);
+
+    assert_eq!(graph.statements.len(), 2);
+  }
+
   #[test]
   fn semicolons() {
     let ast = parse("digraph { a; b; c; }");
@@ -372,6 +796,59 @@ mod tests {
     assert_eq!(ast.statements.len(), 3);
   }
 
+  #[test]
+  fn spans() {
+    let src =
+      "strict digraph G {\n  subgraph cluster_0 { a -> b [color=red] }\n}";
+
+    let ast = parse(src);
+
+    assert_eq!(ast.span, 0..src.len());
+
+    let Statement::Subgraph(subgraph) = &ast.statements[0] else {
+      panic!("expected subgraph");
+    };
+
+    assert_eq!(
+      &src[subgraph.span.clone()],
+      "subgraph cluster_0 { a -> b [color=red] }"
+    );
+
+    let Statement::Edge(edge_statement) = &subgraph.statements[0] else {
+      panic!("expected edge statement");
+    };
+
+    assert_eq!(&src[edge_statement.span.clone()], "a -> b [color=red]");
+    assert_eq!(&src[edge_statement.edges[0].span.clone()], "-> b");
+    assert_eq!(&src[edge_statement.from.span()], "a");
+    assert_eq!(&src[edge_statement.attributes[0].span.clone()], "color=red");
+    assert_eq!(
+      &src[edge_statement.attributes[0]
+        .value
+        .as_ref()
+        .unwrap()
+        .span
+        .clone()],
+      "red",
+    );
+  }
+
+  #[test]
+  fn statement_spans() {
+    let src = "digraph { label = \"hi\"; edge [color=red]; a; }";
+
+    let ast = parse(src);
+
+    assert_eq!(
+      ast
+        .statements
+        .iter()
+        .map(|statement| &src[statement.span()])
+        .collect::<Vec<_>>(),
+      vec!["label = \"hi\"", "edge [color=red]", "a"],
+    );
+  }
+
   #[test]
   fn strict_graph() {
     let ast = parse("strict digraph {}");
@@ -385,7 +862,50 @@ mod tests {
 
     assert_matches!(
       &ast.statements[..],
-      [Statement::Subgraph(Subgraph { id: Some(Id::Ident("cluster_0")), statements })] if statements.len() == 1,
+      [Statement::Subgraph(Subgraph { id: Some(id), statements, .. })] if id.value == "cluster_0" && statements.len() == 1,
+    );
+  }
+
+  #[test]
+  fn unclosed_delimiters() {
+    let src = "digraph { a -> b; subgraph { c [color=red";
+
+    let (_, errors) = super::parse_partial(src);
+
+    assert_eq!(
+      errors
+        .iter()
+        .map(|error| (
+          error.kind,
+          error.message.as_str(),
+          error
+            .labels
+            .iter()
+            .map(|label| label.span.start)
+            .collect::<Vec<_>>(),
+          error.help.as_deref(),
+        ))
+        .collect::<Vec<_>>(),
+      vec![
+        (
+          ErrorKind::UnclosedBracket,
+          "unclosed bracket",
+          vec![31],
+          Some("add a closing `]`"),
+        ),
+        (
+          ErrorKind::UnclosedBrace,
+          "unclosed brace",
+          vec![27],
+          Some("add a closing `}`"),
+        ),
+        (
+          ErrorKind::UnclosedBrace,
+          "unclosed brace",
+          vec![8],
+          Some("add a closing `}`"),
+        ),
+      ],
     );
   }
 
@@ -395,7 +915,7 @@ mod tests {
 
     assert_matches!(
       &ast.statements[..],
-      [Statement::Edge(EdgeStatement { edges, .. })] if edges[0].0 == EdgeOperation::DashDash,
+      [Statement::Edge(EdgeStatement { edges, .. })] if edges[0].operation == EdgeOperation::DashDash,
     );
   }
 }
@@ -0,0 +1,838 @@
+//! The attributes Graphviz understands, which elements they apply to, and
+//! the types of value they accept.
+
+use {
+  super::*,
+  std::fmt::Write,
+  value::{
+    ArrowType, Color, ColorList, Point, PortPos, Rect, SplineType, Style,
+    Value, ValueError,
+  },
+};
+
+const ATTRIBUTES: &[AttributeDefinition] = {
+  use {Element::*, ValueType::*};
+
+  &[
+    attribute("Damping", &[Graph], &[Double]),
+    attribute("K", &[Cluster, Graph], &[Double]),
+    attribute("TBbalance", &[Graph], &[String]),
+    attribute("URL", &[Cluster, Edge, Graph, Node], &[EscString]),
+    attribute("_background", &[Graph], &[String]),
+    attribute("area", &[Cluster, Node], &[Double]),
+    attribute("arrowhead", &[Edge], &[ArrowType]),
+    attribute("arrowsize", &[Edge], &[Double]),
+    attribute("arrowtail", &[Edge], &[ArrowType]),
+    attribute("bb", &[Graph], &[Rect]),
+    attribute("beautify", &[Graph], &[Bool]),
+    attribute("bgcolor", &[Cluster, Graph], &[Color, ColorList]),
+    attribute("center", &[Graph], &[Bool]),
+    attribute("charset", &[Graph], &[String]),
+    attribute("class", &[Cluster, Edge, Graph, Node], &[String]),
+    attribute("cluster", &[Cluster, Node], &[Bool]),
+    attribute("clusterrank", &[Graph], &[ClusterMode]),
+    attribute("color", &[Cluster, Edge, Node], &[Color, ColorList]),
+    attribute("colorscheme", &[Cluster, Edge, Graph, Node], &[String]),
+    attribute("comment", &[Edge, Graph, Node], &[String]),
+    attribute("compound", &[Graph], &[Bool]),
+    attribute("concentrate", &[Graph], &[Bool]),
+    attribute("constraint", &[Edge], &[Bool]),
+    attribute("decorate", &[Edge], &[Bool]),
+    attribute("defaultdist", &[Graph], &[Double]),
+    attribute("dim", &[Graph], &[Int]),
+    attribute("dimen", &[Graph], &[Int]),
+    attribute("dir", &[Edge], &[DirType]),
+    attribute("diredgeconstraints", &[Graph], &[String, Bool]),
+    attribute("distortion", &[Node], &[Double]),
+    attribute("dpi", &[Graph], &[Double]),
+    attribute("edgeURL", &[Edge], &[EscString]),
+    attribute("edgehref", &[Edge], &[EscString]),
+    attribute("edgetarget", &[Edge], &[EscString]),
+    attribute("edgetooltip", &[Edge], &[EscString]),
+    attribute("epsilon", &[Graph], &[Double]),
+    attribute("esep", &[Graph], &[AddDouble, AddPoint]),
+    attribute("fillcolor", &[Cluster, Edge, Node], &[Color, ColorList]),
+    attribute("fixedsize", &[Node], &[Bool, String]),
+    attribute("fontcolor", &[Cluster, Edge, Graph, Node], &[Color]),
+    attribute("fontname", &[Cluster, Edge, Graph, Node], &[String]),
+    attribute("fontnames", &[Graph], &[String]),
+    attribute("fontpath", &[Graph], &[String]),
+    attribute("fontsize", &[Cluster, Edge, Graph, Node], &[Double]),
+    attribute("forcelabels", &[Graph], &[Bool]),
+    attribute("gradientangle", &[Cluster, Graph, Node], &[Int]),
+    attribute("group", &[Node], &[String]),
+    attribute("headURL", &[Edge], &[EscString]),
+    attribute("head_lp", &[Edge], &[Point]),
+    attribute("headclip", &[Edge], &[Bool]),
+    attribute("headhref", &[Edge], &[EscString]),
+    attribute("headlabel", &[Edge], &[LblString]),
+    attribute("headport", &[Edge], &[PortPos]),
+    attribute("headtarget", &[Edge], &[EscString]),
+    attribute("headtooltip", &[Edge], &[EscString]),
+    attribute("height", &[Node], &[Double]),
+    attribute("href", &[Cluster, Edge, Graph, Node], &[EscString]),
+    attribute("id", &[Cluster, Edge, Graph, Node], &[EscString]),
+    attribute("image", &[Node], &[String]),
+    attribute("imagepath", &[Graph], &[String]),
+    attribute("imagepos", &[Node], &[String]),
+    attribute("imagescale", &[Node], &[Bool, String]),
+    attribute("inputscale", &[Graph], &[Double]),
+    attribute("label", &[Cluster, Edge, Graph, Node], &[LblString]),
+    attribute("labelURL", &[Edge], &[EscString]),
+    attribute("label_scheme", &[Graph], &[Int]),
+    attribute("labelangle", &[Edge], &[Double]),
+    attribute("labeldistance", &[Edge], &[Double]),
+    attribute("labelfloat", &[Edge], &[Bool]),
+    attribute("labelfontcolor", &[Edge], &[Color]),
+    attribute("labelfontname", &[Edge], &[String]),
+    attribute("labelfontsize", &[Edge], &[Double]),
+    attribute("labelhref", &[Edge], &[EscString]),
+    attribute("labeljust", &[Cluster, Graph], &[String]),
+    attribute("labelloc", &[Cluster, Graph, Node], &[String]),
+    attribute("labeltarget", &[Edge], &[EscString]),
+    attribute("labeltooltip", &[Edge], &[EscString]),
+    attribute("landscape", &[Graph], &[Bool]),
+    attribute("layer", &[Cluster, Edge, Node], &[LayerRange]),
+    attribute("layerlistsep", &[Graph], &[String]),
+    attribute("layers", &[Graph], &[LayerList]),
+    attribute("layerselect", &[Graph], &[LayerRange]),
+    attribute("layersep", &[Graph], &[String]),
+    attribute("layout", &[Graph], &[String]),
+    attribute("len", &[Edge], &[Double]),
+    attribute("levels", &[Graph], &[Int]),
+    attribute("levelsgap", &[Graph], &[Double]),
+    attribute("lhead", &[Edge], &[String]),
+    attribute("lheight", &[Cluster, Graph], &[Double]),
+    attribute("linelength", &[Graph], &[Int]),
+    attribute("lp", &[Cluster, Edge, Graph], &[Point]),
+    attribute("ltail", &[Edge], &[String]),
+    attribute("lwidth", &[Cluster, Graph], &[Double]),
+    attribute("margin", &[Cluster, Graph, Node], &[Double, Point]),
+    attribute("maxiter", &[Graph], &[Int]),
+    attribute("mclimit", &[Graph], &[Double]),
+    attribute("mindist", &[Graph], &[Double]),
+    attribute("minlen", &[Edge], &[Int]),
+    attribute("mode", &[Graph], &[String]),
+    attribute("model", &[Graph], &[String]),
+    attribute("newrank", &[Graph], &[Bool]),
+    attribute("nodesep", &[Graph], &[Double]),
+    attribute("nojustify", &[Cluster, Edge, Graph, Node], &[Bool]),
+    attribute("normalize", &[Graph], &[Double, Bool]),
+    attribute("notranslate", &[Graph], &[Bool]),
+    attribute("nslimit", &[Graph], &[Double]),
+    attribute("nslimit1", &[Graph], &[Double]),
+    attribute("oneblock", &[Graph], &[Bool]),
+    attribute("ordering", &[Graph, Node], &[String]),
+    attribute("orientation", &[Graph, Node], &[Double, String]),
+    attribute("outputorder", &[Graph], &[OutputMode]),
+    attribute("overlap", &[Graph], &[String, Bool]),
+    attribute("overlap_scaling", &[Graph], &[Double]),
+    attribute("overlap_shrink", &[Graph], &[Bool]),
+    attribute("pack", &[Graph], &[Bool, Int]),
+    attribute("packmode", &[Graph], &[PackMode]),
+    attribute("pad", &[Graph], &[Double, Point]),
+    attribute("page", &[Graph], &[Double, Point]),
+    attribute("pagedir", &[Graph], &[PageDir]),
+    attribute("pencolor", &[Cluster], &[Color]),
+    attribute("penwidth", &[Cluster, Edge, Node], &[Double]),
+    attribute("peripheries", &[Cluster, Node], &[Int]),
+    attribute("pin", &[Node], &[Bool]),
+    attribute("pos", &[Edge, Node], &[Point, SplineType]),
+    attribute("quadtree", &[Graph], &[QuadType, Bool]),
+    attribute("quantum", &[Graph], &[Double]),
+    attribute("radius", &[Cluster, Node], &[Double]),
+    attribute("rank", &[Subgraph], &[RankType]),
+    attribute("rankdir", &[Graph], &[RankDir]),
+    attribute("ranksep", &[Graph], &[RankSep]),
+    attribute("ratio", &[Graph], &[Double, String]),
+    attribute("rects", &[Node], &[Rect]),
+    attribute("regular", &[Node], &[Bool]),
+    attribute("remincross", &[Graph], &[Bool]),
+    attribute("repulsiveforce", &[Graph], &[Double]),
+    attribute("resolution", &[Graph], &[Double]),
+    attribute("root", &[Graph, Node], &[String, Bool]),
+    attribute("rotate", &[Graph], &[Int]),
+    attribute("rotation", &[Graph], &[Double]),
+    attribute("samehead", &[Edge], &[String]),
+    attribute("sametail", &[Edge], &[String]),
+    attribute("samplepoints", &[Node], &[Int]),
+    attribute("scale", &[Graph], &[Double, Point]),
+    attribute("searchsize", &[Graph], &[Int]),
+    attribute("sep", &[Graph], &[AddDouble, AddPoint]),
+    attribute("shape", &[Node], &[Shape]),
+    attribute("shapefile", &[Node], &[String]),
+    attribute("showboxes", &[Edge, Graph, Node], &[Int]),
+    attribute("sides", &[Node], &[Int]),
+    attribute("size", &[Graph], &[Double, Point]),
+    attribute("skew", &[Node], &[Double]),
+    attribute("smoothing", &[Graph], &[SmoothType]),
+    attribute("sortv", &[Cluster, Graph, Node], &[Int]),
+    attribute("splines", &[Graph], &[Bool, String]),
+    attribute("start", &[Graph], &[StartType]),
+    attribute("style", &[Cluster, Edge, Graph, Node], &[Style]),
+    attribute("stylesheet", &[Graph], &[String]),
+    attribute("tailURL", &[Edge], &[EscString]),
+    attribute("tail_lp", &[Edge], &[Point]),
+    attribute("tailclip", &[Edge], &[Bool]),
+    attribute("tailhref", &[Edge], &[EscString]),
+    attribute("taillabel", &[Edge], &[LblString]),
+    attribute("tailport", &[Edge], &[PortPos]),
+    attribute("tailtarget", &[Edge], &[EscString]),
+    attribute("tailtooltip", &[Edge], &[EscString]),
+    attribute(
+      "target",
+      &[Cluster, Edge, Graph, Node],
+      &[EscString, String],
+    ),
+    attribute("tooltip", &[Cluster, Edge, Node], &[EscString]),
+    attribute("truecolor", &[Graph], &[Bool]),
+    attribute("vertices", &[Node], &[PointList]),
+    attribute("viewport", &[Graph], &[ViewPort]),
+    attribute("voro_margin", &[Graph], &[Double]),
+    attribute("weight", &[Edge], &[Int, Double]),
+    attribute("width", &[Node], &[Double]),
+    attribute("xdotversion", &[Graph], &[String]),
+    attribute("xlabel", &[Edge, Node], &[LblString]),
+    attribute("xlp", &[Edge, Node], &[Point]),
+    attribute("z", &[Node], &[Double]),
+  ]
+};
+
+const CLUSTER_MODES: &[&str] = &["global", "local", "none"];
+
+const DIR_TYPES: &[&str] = &["back", "both", "forward", "none"];
+
+const OUTPUT_MODES: &[&str] = &["breadthfirst", "edgesfirst", "nodesfirst"];
+
+const PACK_MODES: &[&str] = &["array", "clust", "graph", "node"];
+
+const PAGE_DIRS: &[&str] = &["BL", "BR", "LB", "LT", "RB", "RT", "TL", "TR"];
+
+const QUAD_TYPES: &[&str] = &["fast", "none", "normal"];
+
+const RANK_DIRS: &[&str] = &["BT", "LR", "RL", "TB"];
+
+const RANK_TYPES: &[&str] = &["max", "min", "same", "sink", "source"];
+
+const SHAPES: &[&str] = &[
+  "Mcircle",
+  "Mdiamond",
+  "Mrecord",
+  "Msquare",
+  "assembly",
+  "box",
+  "box3d",
+  "cds",
+  "circle",
+  "component",
+  "cylinder",
+  "diamond",
+  "doublecircle",
+  "doubleoctagon",
+  "egg",
+  "ellipse",
+  "epsf",
+  "fivepoverhang",
+  "folder",
+  "hexagon",
+  "house",
+  "insulator",
+  "invhouse",
+  "invtrapezium",
+  "invtriangle",
+  "larrow",
+  "lpromoter",
+  "none",
+  "note",
+  "noverhang",
+  "octagon",
+  "oval",
+  "parallelogram",
+  "pentagon",
+  "plain",
+  "plaintext",
+  "point",
+  "polygon",
+  "primersite",
+  "promoter",
+  "proteasesite",
+  "proteinstab",
+  "rarrow",
+  "record",
+  "rect",
+  "rectangle",
+  "restrictionsite",
+  "ribosite",
+  "rnastab",
+  "rpromoter",
+  "septagon",
+  "signature",
+  "square",
+  "star",
+  "tab",
+  "terminator",
+  "threepoverhang",
+  "trapezium",
+  "triangle",
+  "tripleoctagon",
+  "underline",
+  "utr",
+];
+
+const SMOOTH_TYPES: &[&str] = &[
+  "avg_dist",
+  "graph_dist",
+  "none",
+  "power_dist",
+  "rng",
+  "spring",
+  "triangle",
+];
+
+const START_TYPES: &[&str] = &["random", "regular", "self"];
+
+/// A Graphviz attribute: its name, the elements it applies to, and the
+/// types of value it accepts.
+#[derive(Debug, PartialEq)]
+pub struct AttributeDefinition {
+  pub name: &'static str,
+  pub types: &'static [ValueType],
+  pub used_by: &'static [Element],
+}
+
+impl AttributeDefinition {
+  /// Whether `value` is valid for any of this attribute's types.
+  #[must_use]
+  pub fn accepts(&self, value: &str) -> bool {
+    self.check(value).is_ok()
+  }
+
+  /// Whether this attribute has an effect when set on `element`.
+  ///
+  /// Attributes set on the root graph or on a subgraph are inherited by the
+  /// clusters and subgraphs nested inside it, so graph level attributes
+  /// accept any attribute that applies to one of those.
+  #[must_use]
+  pub fn applies_to(&self, element: Element) -> bool {
+    let accepted: &[Element] = match element {
+      Element::Cluster | Element::Subgraph => {
+        &[Element::Cluster, Element::Subgraph]
+      }
+      Element::Edge => &[Element::Edge],
+      Element::Graph => &[Element::Cluster, Element::Graph, Element::Subgraph],
+      Element::Node => &[Element::Node],
+    };
+
+    self
+      .used_by
+      .iter()
+      .any(|used_by| accepted.contains(used_by))
+  }
+
+  /// Check `value` against each of this attribute's types.
+  ///
+  /// If no type accepts the value, the error that got furthest into the
+  /// value is returned, since that is usually the type the author meant.
+  pub fn check(&self, value: &str) -> Result<(), ValueError> {
+    let mut best: Option<ValueError> = None;
+
+    for ty in self.types {
+      match ty.check(value) {
+        Ok(()) => return Ok(()),
+        Err(error) => {
+          if best
+            .as_ref()
+            .is_none_or(|best| error.span.start >= best.span.start)
+          {
+            best = Some(error);
+          }
+        }
+      }
+    }
+
+    best.map_or(Ok(()), Err)
+  }
+}
+
+/// The kinds of element an attribute can be set on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Element {
+  Cluster,
+  Edge,
+  Graph,
+  Node,
+  Subgraph,
+}
+
+impl Element {
+  #[must_use]
+  pub fn plural(self) -> &'static str {
+    match self {
+      Element::Cluster => "clusters",
+      Element::Edge => "edges",
+      Element::Graph => "graphs",
+      Element::Node => "nodes",
+      Element::Subgraph => "subgraphs",
+    }
+  }
+}
+
+/// The value types used by Graphviz attributes, named after the types in the
+/// Graphviz attribute documentation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ValueType {
+  AddDouble,
+  AddPoint,
+  ArrowType,
+  Bool,
+  ClusterMode,
+  Color,
+  ColorList,
+  DirType,
+  Double,
+  DoubleList,
+  EscString,
+  Int,
+  LayerList,
+  LayerRange,
+  LblString,
+  OutputMode,
+  PackMode,
+  PageDir,
+  Point,
+  PointList,
+  PortPos,
+  QuadType,
+  RankDir,
+  RankSep,
+  RankType,
+  Rect,
+  Shape,
+  SmoothType,
+  SplineType,
+  StartType,
+  String,
+  Style,
+  ViewPort,
+}
+
+impl ValueType {
+  /// Whether `value` is a valid value of this type.
+  #[must_use]
+  pub fn accepts(self, value: &str) -> bool {
+    self.check(value).is_ok()
+  }
+
+  /// Check that `value` is a valid value of this type, returning an error
+  /// whose span is relative to the start of `value` if it is not.
+  ///
+  /// The empty string is valid for every type, since Graphviz takes it to
+  /// mean the attribute's default.
+  pub fn check(self, value: &str) -> Result<(), ValueError> {
+    fn parse<'a, T: Value<'a>>(value: &'a str) -> Result<(), ValueError> {
+      T::parse(value).map(|_| ())
+    }
+
+    if value.is_empty() {
+      return Ok(());
+    }
+
+    let expected = || {
+      let mut message = format!("expected {}", self.description());
+
+      if let Some(suggestion) = suggest(value, self.keywords().iter().copied())
+      {
+        write!(message, ", did you mean `{suggestion}`?").unwrap();
+      }
+
+      ValueError::new(message, 0..value.len())
+    };
+
+    let (unsigned, sign) = match value.strip_prefix('+') {
+      Some(unsigned) => (unsigned, 1),
+      None => (value, 0),
+    };
+
+    match self {
+      ValueType::AddDouble => {
+        parse::<f64>(unsigned).map_err(|error| error.offset(sign))
+      }
+      ValueType::AddPoint => {
+        parse::<Point>(unsigned).map_err(|error| error.offset(sign))
+      }
+      ValueType::ArrowType => parse::<ArrowType>(value),
+      ValueType::Bool => parse::<bool>(value),
+      ValueType::Color => parse::<Color>(value),
+      ValueType::ColorList => parse::<ColorList>(value),
+      ValueType::Double => parse::<f64>(value),
+      ValueType::DoubleList => value.split(':').try_for_each(|number| {
+        value::number(number)
+          .map(|_| ())
+          .map_err(|error| error.offset(value::offset(value, number)))
+      }),
+      ValueType::EscString
+      | ValueType::LayerList
+      | ValueType::LayerRange
+      | ValueType::LblString
+      | ValueType::String => Ok(()),
+      ValueType::Int => value
+        .trim()
+        .parse::<i64>()
+        .map(|_| ())
+        .map_err(|_| expected()),
+      ValueType::PackMode => {
+        let mode = value.trim_end_matches(|c: char| c.is_ascii_digit());
+
+        if PACK_MODES.contains(&mode)
+          || mode
+            .strip_prefix("array_")
+            .is_some_and(|flags| flags.chars().all(|c| "bclrtu".contains(c)))
+        {
+          Ok(())
+        } else {
+          Err(expected())
+        }
+      }
+      ValueType::Point => parse::<Point>(value),
+      ValueType::PointList => value.split_whitespace().try_for_each(|point| {
+        parse::<Point>(point)
+          .map_err(|error| error.offset(value::offset(value, point)))
+      }),
+      ValueType::PortPos => parse::<PortPos>(value),
+      // A number or list of numbers, optionally followed by `equally`, which
+      // can also stand on its own to keep the default separation.
+      ValueType::RankSep => {
+        let separation = value
+          .trim_end()
+          .strip_suffix("equally")
+          .map_or(value, str::trim_end);
+
+        if separation.is_empty() {
+          Ok(())
+        } else {
+          ValueType::DoubleList.check(separation)
+        }
+      }
+      ValueType::Rect => parse::<Rect>(value),
+      ValueType::SplineType => parse::<SplineType>(value),
+      ValueType::StartType => {
+        let start = value.trim_end_matches(|c: char| c.is_ascii_digit());
+
+        if start.is_empty() || START_TYPES.contains(&start) {
+          Ok(())
+        } else {
+          Err(expected())
+        }
+      }
+      ValueType::Style => parse::<Style>(value),
+      ValueType::ViewPort => {
+        let fields = value.split(',').collect::<Vec<_>>();
+
+        let numbers = match fields.len() {
+          4 => &fields[..3],
+          5 => &fields[..],
+          _ => {
+            return Err(ValueError::new(
+              "expected a viewport of the form `W,H,Z,x,y` or `W,H,Z,N`",
+              0..value.len(),
+            ));
+          }
+        };
+
+        numbers.iter().try_for_each(|number| {
+          value::number(number)
+            .map(|_| ())
+            .map_err(|error| error.offset(value::offset(value, number)))
+        })
+      }
+      ValueType::ClusterMode
+      | ValueType::DirType
+      | ValueType::OutputMode
+      | ValueType::PageDir
+      | ValueType::QuadType
+      | ValueType::RankDir
+      | ValueType::RankType
+      | ValueType::Shape
+      | ValueType::SmoothType => {
+        if self.keywords().contains(&value) {
+          Ok(())
+        } else {
+          Err(expected())
+        }
+      }
+    }
+  }
+
+  /// A short description of the values this type accepts, for use in
+  /// diagnostics.
+  #[must_use]
+  pub fn description(self) -> &'static str {
+    match self {
+      ValueType::AddDouble => "a number, optionally prefixed with `+`",
+      ValueType::AddPoint => "a point, optionally prefixed with `+`",
+      ValueType::ArrowType => "an arrow type",
+      ValueType::Bool => "a boolean",
+      ValueType::ClusterMode => "a cluster mode",
+      ValueType::Color => "a color",
+      ValueType::ColorList => "a color list",
+      ValueType::DirType => "an edge direction",
+      ValueType::Double => "a number",
+      ValueType::DoubleList => "a `:` separated list of numbers",
+      ValueType::EscString | ValueType::String => "a string",
+      ValueType::Int => "an integer",
+      ValueType::LayerList => "a layer list",
+      ValueType::LayerRange => "a layer range",
+      ValueType::LblString => "a label",
+      ValueType::OutputMode => "an output order",
+      ValueType::PackMode => "a pack mode",
+      ValueType::PageDir => "a page direction",
+      ValueType::Point => "a point",
+      ValueType::PointList => "a list of points",
+      ValueType::PortPos => "a port position",
+      ValueType::QuadType => "a quadtree type",
+      ValueType::RankDir => "a rank direction",
+      ValueType::RankSep => {
+        "a number or `:` separated list of numbers, optionally followed by \
+         `equally`"
+      }
+      ValueType::RankType => "a rank type",
+      ValueType::Rect => "a rectangle",
+      ValueType::Shape => "a node shape",
+      ValueType::SmoothType => "a smoothing type",
+      ValueType::SplineType => "a spline",
+      ValueType::StartType => "a start type",
+      ValueType::Style => "a style",
+      ValueType::ViewPort => "a viewport",
+    }
+  }
+
+  /// The keywords this type accepts, used to suggest corrections for
+  /// misspelled values. Empty for types that are not enumerations, and for
+  /// types whose parser makes its own suggestions.
+  #[must_use]
+  pub fn keywords(self) -> &'static [&'static str] {
+    match self {
+      ValueType::Bool => &["false", "true"],
+      ValueType::ClusterMode => CLUSTER_MODES,
+      ValueType::DirType => DIR_TYPES,
+      ValueType::OutputMode => OUTPUT_MODES,
+      ValueType::PackMode => PACK_MODES,
+      ValueType::PageDir => PAGE_DIRS,
+      ValueType::QuadType => QUAD_TYPES,
+      ValueType::RankDir => RANK_DIRS,
+      ValueType::RankType => RANK_TYPES,
+      ValueType::Shape => SHAPES,
+      ValueType::SmoothType => SMOOTH_TYPES,
+      ValueType::StartType => START_TYPES,
+      _ => &[],
+    }
+  }
+}
+
+const fn attribute(
+  name: &'static str,
+  used_by: &'static [Element],
+  types: &'static [ValueType],
+) -> AttributeDefinition {
+  AttributeDefinition {
+    name,
+    types,
+    used_by,
+  }
+}
+
+/// Every attribute Graphviz understands, sorted by name.
+#[must_use]
+pub fn attributes() -> &'static [AttributeDefinition] {
+  ATTRIBUTES
+}
+
+/// The Levenshtein distance between `a` and `b`, ignoring ASCII case.
+fn distance(a: &str, b: &str) -> usize {
+  let a = a.to_ascii_lowercase().chars().collect::<Vec<_>>();
+  let b = b.to_ascii_lowercase().chars().collect::<Vec<_>>();
+
+  let mut previous = (0..=b.len()).collect::<Vec<_>>();
+
+  for (i, ca) in a.iter().enumerate() {
+    let mut current = vec![i + 1];
+
+    for (j, cb) in b.iter().enumerate() {
+      current.push(
+        (previous[j] + usize::from(ca != cb))
+          .min(previous[j + 1] + 1)
+          .min(current[j] + 1),
+      );
+    }
+
+    previous = current;
+  }
+
+  previous[b.len()]
+}
+
+/// Look up the definition of the attribute called `name`. Attribute names
+/// are case-sensitive.
+#[must_use]
+pub fn lookup(name: &str) -> Option<&'static AttributeDefinition> {
+  ATTRIBUTES
+    .binary_search_by_key(&name, |attribute| attribute.name)
+    .ok()
+    .map(|index| &ATTRIBUTES[index])
+}
+
+/// Suggest the candidate closest to `name`, if any is close enough to be a
+/// plausible typo.
+pub(crate) fn suggest<'a>(
+  name: &str,
+  candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+  candidates
+    .into_iter()
+    .map(|candidate| (distance(name, candidate), candidate))
+    .filter(|(distance, candidate)| {
+      *distance
+        <= (name.chars().count().max(candidate.chars().count()) / 3).max(1)
+    })
+    .min_by_key(|(distance, _)| *distance)
+    .map(|(_, candidate)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn arrow_types() {
+    for value in [
+      "normal",
+      "inv",
+      "onormal",
+      "lteeoldiamond",
+      "ediamond",
+      "veevee",
+    ] {
+      assert!(ValueType::ArrowType.accepts(value), "{value}");
+    }
+
+    for value in ["nrmal", "normalnormalnormalnormalnormal", "x"] {
+      assert!(!ValueType::ArrowType.accepts(value), "{value}");
+    }
+  }
+
+  #[test]
+  fn attributes_are_sorted() {
+    assert!(
+      ATTRIBUTES
+        .windows(2)
+        .all(|pair| pair[0].name < pair[1].name)
+    );
+  }
+
+  #[test]
+  fn checks() {
+    let error = lookup("color").unwrap().check("red:blu").unwrap_err();
+
+    assert_eq!(error.message, "unknown color `blu`, did you mean `blue`?");
+    assert_eq!(error.span, 4..7);
+
+    let error = lookup("color")
+      .unwrap()
+      .check("red;0.6:blue;0.6")
+      .unwrap_err();
+
+    assert_eq!(error.message, "color weights must add up to at most 1");
+    assert_eq!(error.span, 13..16);
+
+    let error = ValueType::AddPoint.check("+1,x").unwrap_err();
+
+    assert_eq!(error.span, 3..4);
+
+    for attribute in ATTRIBUTES {
+      assert!(attribute.check("").is_ok(), "{}", attribute.name);
+    }
+
+    let error = ValueType::Shape.check("bx").unwrap_err();
+
+    assert_eq!(error.message, "expected a node shape, did you mean `box`?");
+  }
+
+  #[test]
+  fn colors() {
+    for value in [
+      "red",
+      "#ff0000",
+      "#ff000080",
+      "0.5 0.5 1",
+      "0.1,0.2,0.3",
+      "/x11/red",
+    ] {
+      assert!(ValueType::Color.accepts(value), "{value}");
+    }
+
+    for value in ["#ff00", "#gggggg", "red blue"] {
+      assert!(!ValueType::Color.accepts(value), "{value}");
+    }
+
+    assert!(ValueType::ColorList.accepts("red:blue;0.3:green"));
+    assert!(ValueType::ColorList.accepts("red;0.33:blue;0.56:green;0.11"));
+    assert!(!ValueType::ColorList.accepts("red;x"));
+  }
+
+  #[test]
+  fn distances() {
+    assert_eq!(distance("color", "color"), 0);
+    assert_eq!(distance("colr", "color"), 1);
+    assert_eq!(distance("shape", "shpae"), 2);
+    assert_eq!(distance("", "abc"), 3);
+  }
+
+  #[test]
+  fn lookups() {
+    assert_eq!(lookup("shape").unwrap().types, &[ValueType::Shape]);
+    assert_eq!(lookup("URL").unwrap().name, "URL");
+    assert!(lookup("url").is_none());
+    assert!(lookup("colr").is_none());
+  }
+
+  #[test]
+  fn points_and_rects() {
+    assert!(ValueType::Point.accepts("1,2"));
+    assert!(ValueType::Point.accepts("1.5,-2!"));
+    assert!(ValueType::Point.accepts("1,2,3"));
+    assert!(!ValueType::Point.accepts("1"));
+    assert!(ValueType::Rect.accepts("0,0,10,20.5"));
+    assert!(!ValueType::Rect.accepts("0,0,10"));
+    assert!(ValueType::SplineType.accepts("e,1,2 0,0 1,1 2,2 3,3"));
+    assert!(!ValueType::SplineType.accepts("0,0 1,1"));
+  }
+
+  #[test]
+  fn rank_separations() {
+    for value in ["1.2", "1.2 equally", "0.5:1:2 equally", "equally"] {
+      assert!(ValueType::RankSep.accepts(value), "{value}");
+    }
+
+    for value in ["equally 1.2", "1.2 equaly", "1.2equally x"] {
+      assert!(!ValueType::RankSep.accepts(value), "{value}");
+    }
+
+    assert!(lookup("ranksep").unwrap().check("1.2 equally").is_ok());
+  }
+
+  #[test]
+  fn styles() {
+    assert!(ValueType::Style.accepts("filled,rounded"));
+    assert!(ValueType::Style.accepts("setlinewidth(2)"));
+    assert!(!ValueType::Style.accepts("filed"));
+  }
+
+  #[test]
+  fn suggestions() {
+    let names = || ATTRIBUTES.iter().map(|attribute| attribute.name);
+
+    assert_eq!(suggest("colr", names()), Some("color"));
+    assert_eq!(suggest("fontsze", names()), Some("fontsize"));
+    assert_eq!(suggest("xyzzy", names()), None);
+  }
+}
@@ -7,6 +7,7 @@ pub(crate) enum Token<'src> {
   CloseBracket,
   Colon,
   Comma,
+  Comment(&'src str),
   DashDash,
   Digraph,
   Edge,
@@ -32,15 +33,15 @@ impl Display for Token<'_> {
       Token::CloseBracket => write!(f, "]"),
       Token::Colon => write!(f, ":"),
       Token::Comma => write!(f, ","),
+      Token::Comment(s) | Token::Ident(s) | Token::Number(s) => {
+        write!(f, "{s}")
+      }
       Token::DashDash => write!(f, "--"),
       Token::Digraph => write!(f, "digraph"),
       Token::Edge => write!(f, "edge"),
       Token::Equals => write!(f, "="),
       Token::Graph => write!(f, "graph"),
       Token::HtmlString(s) => write!(f, "<{s}>"),
-      Token::Ident(s) | Token::Number(s) => {
-        write!(f, "{s}")
-      }
       Token::Node => write!(f, "node"),
       Token::OpenBrace => write!(f, "{{"),
       Token::OpenBracket => write!(f, "["),
@@ -0,0 +1,411 @@
+use {
+  super::*,
+  schema::Element,
+  visit::{self, Visit},
+};
+
+struct Validator {
+  element: Element,
+  errors: Vec<ParseError>,
+  kind: GraphKind,
+}
+
+impl Validator {
+  fn attribute(
+    &mut self,
+    element: Element,
+    key: &Id<'_>,
+    value: Option<&Id<'_>>,
+  ) {
+    let name = key.decoded();
+
+    let Some(definition) = schema::lookup(&name) else {
+      let suggestion = schema::suggest(
+        &name,
+        schema::attributes().iter().map(|attribute| attribute.name),
+      );
+
+      self.errors.push(
+        ParseError::new(
+          ErrorKind::UnknownAttribute,
+          match suggestion {
+            Some(suggestion) => {
+              format!(
+                "unknown attribute `{name}`, did you mean `{suggestion}`?"
+              )
+            }
+            None => format!("unknown attribute `{name}`"),
+          },
+          key.span.clone(),
+        )
+        .warning(),
+      );
+
+      return;
+    };
+
+    if !definition.applies_to(element) {
+      self.errors.push(
+        ParseError::new(
+          ErrorKind::AttributeNotApplicable,
+          format!("attribute `{name}` does not apply to {}", element.plural()),
+          key.span.clone(),
+        )
+        .with_note(format!(
+          "`{name}` applies to {}",
+          definition
+            .used_by
+            .iter()
+            .map(|element| element.plural())
+            .collect::<Vec<_>>()
+            .join(", "),
+        ))
+        .warning(),
+      );
+
+      return;
+    }
+
+    let text = value.map_or(Cow::Borrowed("true"), Id::decoded);
+
+    let Err(error) = definition.check(&text) else {
+      return;
+    };
+
+    let error = ParseError::new(
+      ErrorKind::InvalidAttributeValue,
+      format!(
+        "invalid value `{text}` for attribute `{}`: {}",
+        definition.name, error.message,
+      ),
+      // Offsets into a decoded value only map back to the source if decoding
+      // left it unchanged.
+      match value {
+        Some(value) if matches!(text, Cow::Borrowed(_)) => {
+          let start = value.value_start();
+          start + error.span.start..start + error.span.end
+        }
+        Some(value) => value.span.clone(),
+        None => key.span.clone(),
+      },
+    );
+
+    // Graphviz ignores a value it cannot use, and warns about it.
+    self.errors.push(
+      match value {
+        Some(_) => error.with_label(
+          format!("`{}` is set here", definition.name),
+          key.span.clone(),
+        ),
+        None => error,
+      }
+      .warning(),
+    );
+  }
+
+  fn attributes(&mut self, element: Element, attributes: &[Attribute<'_>]) {
+    for attribute in attributes {
+      self.attribute(element, &attribute.key, attribute.value.as_ref());
+    }
+  }
+}
+
+impl Visit<'_, '_> for Validator {
+  fn visit_attribute_statement(&mut self, statement: &AttributeStatement<'_>) {
+    self.attributes(
+      match statement.target {
+        AttributeTarget::Edge => Element::Edge,
+        AttributeTarget::Graph => self.element,
+        AttributeTarget::Node => Element::Node,
+      },
+      &statement.attributes,
+    );
+  }
+
+  fn visit_edge(&mut self, edge: &Edge<'_>) {
+    let expected = match self.kind {
+      GraphKind::Digraph => EdgeOperation::Arrow,
+      GraphKind::Graph => EdgeOperation::DashDash,
+    };
+
+    if edge.operation != expected {
+      self.errors.push(
+        ParseError::new(
+          ErrorKind::InvalidEdgeOperation,
+          format!(
+            "edge operator `{}` is not allowed in {} graphs, use `{expected}`",
+            edge.operation,
+            match self.kind {
+              GraphKind::Digraph => "directed",
+              GraphKind::Graph => "undirected",
+            },
+          ),
+          edge.span.start..edge.span.start + 2,
+        )
+        .with_help(match self.kind {
+          GraphKind::Digraph => {
+            "declare the graph with `graph` to use undirected edges"
+          }
+          GraphKind::Graph => {
+            "declare the graph with `digraph` to use directed edges"
+          }
+        }),
+      );
+    }
+
+    visit::visit_edge(self, edge);
+  }
+
+  fn visit_edge_statement(&mut self, statement: &EdgeStatement<'_>) {
+    self.visit_edge_target(&statement.from);
+
+    for edge in &statement.edges {
+      self.visit_edge(edge);
+    }
+
+    self.attributes(Element::Edge, &statement.attributes);
+  }
+
+  fn visit_node_statement(&mut self, statement: &NodeStatement<'_>) {
+    self.attributes(Element::Node, &statement.attributes);
+  }
+
+  fn visit_statement(&mut self, statement: &Statement<'_>) {
+    match statement {
+      Statement::Assign(key, value) => {
+        self.attribute(self.element, key, Some(value));
+      }
+      statement => visit::visit_statement(self, statement),
+    }
+  }
+
+  fn visit_subgraph(&mut self, subgraph: &Subgraph<'_>) {
+    let cluster = subgraph.id.as_ref().is_some_and(|id| {
+      id.value
+        .get(..7)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("cluster"))
+    });
+
+    let parent = self.element;
+
+    self.element = if cluster {
+      Element::Cluster
+    } else {
+      Element::Subgraph
+    };
+
+    visit::visit_subgraph(self, subgraph);
+
+    self.element = parent;
+  }
+}
+
+pub(crate) fn validate(graph: &Graph<'_>) -> Vec<ParseError> {
+  let mut validator = Validator {
+    element: Element::Graph,
+    errors: Vec::new(),
+    kind: graph.kind.clone(),
+  };
+
+  validator.visit_graph(graph);
+
+  validator.errors
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn arrow_in_undirected_graph() {
+    let src = "graph { a -- b -> c }";
+
+    let errors = validate(&parse(src).unwrap());
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(&src[errors[0].span.clone()], "->");
+    assert_eq!(
+      errors[0].message,
+      "edge operator `->` is not allowed in undirected graphs, use `--`",
+    );
+  }
+
+  #[test]
+  fn attribute_in_wrong_context() {
+    let src = "digraph { subgraph s { rankdir=LR } a [rankdir=LR] }";
+
+    let errors = validate(&parse(src).unwrap());
+
+    assert_eq!(
+      errors
+        .iter()
+        .map(|error| (&src[error.span.clone()], error.message.as_str()))
+        .collect::<Vec<_>>(),
+      vec![
+        ("rankdir", "attribute `rankdir` does not apply to subgraphs"),
+        ("rankdir", "attribute `rankdir` does not apply to nodes"),
+      ],
+    );
+  }
+
+  #[test]
+  fn attribute_values() {
+    let src =
+      "digraph { a [shape=bx, width=wide, color=\"#ff\"] b -> c [dir=both] }";
+
+    let errors = validate(&parse(src).unwrap());
+
+    assert_eq!(
+      errors
+        .iter()
+        .map(|error| (&src[error.span.clone()], error.message.as_str()))
+        .collect::<Vec<_>>(),
+      vec![
+        (
+          "bx",
+          "invalid value `bx` for attribute `shape`: expected a node shape, did you mean `box`?",
+        ),
+        (
+          "wide",
+          "invalid value `wide` for attribute `width`: expected a number, found `wide`",
+        ),
+        (
+          "ff",
+          "invalid value `#ff` for attribute `color`: expected 6 or 8 hexadecimal digits after `#`, found 2",
+        ),
+      ],
+    );
+
+    assert!(errors.iter().all(|error| !error.is_error()));
+  }
+
+  #[test]
+  fn clusters() {
+    let src = "digraph { subgraph cluster_a { color=red; bgcolor=blue } }";
+
+    assert!(validate(&parse(src).unwrap()).is_empty());
+  }
+
+  #[test]
+  fn concatenated_values() {
+    let src = r#"digraph { a [shape="b" + "ox", color="re" + "d d"] }"#;
+
+    let errors = validate(&parse(src).unwrap());
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(&src[errors[0].span.clone()], r#""re" + "d d""#);
+  }
+
+  #[test]
+  fn dashdash_in_directed_graph() {
+    let src = "digraph { a -- b }";
+
+    let errors = validate(&parse(src).unwrap());
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(&src[errors[0].span.clone()], "--");
+  }
+
+  #[test]
+  fn empty_values() {
+    let src = r#"digraph { a [shape="", style="", width="", fixedsize] }"#;
+
+    assert!(validate(&parse(src).unwrap()).is_empty());
+  }
+
+  #[test]
+  fn error_details() {
+    let src = "graph { a -> b; c [rankdir=LR, shape=bx] }";
+
+    let errors = validate(&parse(src).unwrap());
+
+    assert_eq!(
+      errors.iter().map(|error| error.kind).collect::<Vec<_>>(),
+      vec![
+        ErrorKind::InvalidEdgeOperation,
+        ErrorKind::AttributeNotApplicable,
+        ErrorKind::InvalidAttributeValue,
+      ],
+    );
+
+    assert_eq!(
+      errors
+        .iter()
+        .map(|error| error.severity)
+        .collect::<Vec<_>>(),
+      vec![Severity::Error, Severity::Warning, Severity::Warning],
+    );
+
+    assert_eq!(
+      errors[0].help.as_deref(),
+      Some("declare the graph with `digraph` to use directed edges"),
+    );
+    assert_eq!(errors[1].notes, vec!["`rankdir` applies to graphs"]);
+    assert_eq!(
+      errors[2]
+        .labels
+        .iter()
+        .map(|label| (&src[label.span.clone()], label.message.as_str()))
+        .collect::<Vec<_>>(),
+      vec![("shape", "`shape` is set here")],
+    );
+  }
+
+  #[test]
+  fn nested_subgraphs() {
+    let src = "digraph { subgraph s { a -> { b -- c } } x -> { y -- z } }";
+
+    let errors = validate(&parse(src).unwrap());
+
+    assert_eq!(
+      errors
+        .iter()
+        .map(|error| error.span.start)
+        .collect::<Vec<_>>(),
+      vec![32, 50],
+    );
+  }
+
+  #[test]
+  fn unknown_attributes() {
+    let src = "digraph { colr=red; node [fontsze=12]; a [xyzzy=1] }";
+
+    let errors = validate(&parse(src).unwrap());
+
+    assert_eq!(
+      errors
+        .iter()
+        .map(|error| (&src[error.span.clone()], error.message.as_str()))
+        .collect::<Vec<_>>(),
+      vec![
+        ("colr", "unknown attribute `colr`, did you mean `color`?"),
+        (
+          "fontsze",
+          "unknown attribute `fontsze`, did you mean `fontsize`?"
+        ),
+        ("xyzzy", "unknown attribute `xyzzy`"),
+      ],
+    );
+
+    assert!(errors.iter().all(|error| !error.is_error()));
+  }
+
+  #[test]
+  fn valid() {
+    assert!(
+      validate(&parse("digraph { a -> b -> { c d } }").unwrap()).is_empty()
+    );
+    assert!(
+      validate(&parse("graph { a -- b -- { c d } }").unwrap()).is_empty()
+    );
+    assert!(
+      validate(
+        &parse(
+          "digraph { rankdir=LR; node [shape=box, style=\"filled,rounded\"]; a -> b [arrowhead=onormal, penwidth=2] }"
+        )
+        .unwrap()
+      )
+      .is_empty()
+    );
+  }
+}
@@ -0,0 +1,1378 @@
+//! Typed attribute values.
+//!
+//! Attribute values are plain strings in DOT, but many attributes expect a
+//! value with structure of its own, such as a color, a point or a list of
+//! styles. The types in this module parse those strings. They can be parsed
+//! directly from a string with [`Value::parse`], in which case error spans are
+//! byte ranges into that string, or from an [`Id`] with [`Id::parse`], in which
+//! case error spans point into the DOT source.
+
+use {
+  super::*,
+  colors::{SVG, X11},
+  schema::suggest,
+};
+
+pub(crate) const ARROW_SHAPES: &[&str] = &[
+  "box", "crow", "curve", "diamond", "dot", "icurve", "inv", "none", "normal",
+  "tee", "vee",
+];
+
+const COMPASS_POINTS: &[&str] =
+  &["_", "c", "e", "n", "ne", "nw", "s", "se", "sw", "w"];
+
+/// Arrow names from older versions of Graphviz, and the arrows they stand
+/// for.
+const LEGACY_ARROW_TYPES: &[(&str, &str)] = &[
+  ("ediamond", "odiamond"),
+  ("empty", "onormal"),
+  ("halfopen", "lvee"),
+  ("invempty", "oinv"),
+  ("open", "vee"),
+];
+
+const MAX_ARROW_SHAPES: usize = 4;
+
+pub(crate) const STYLES: &[&str] = &[
+  "bold",
+  "dashed",
+  "diagonals",
+  "dotted",
+  "filled",
+  "invis",
+  "radial",
+  "rounded",
+  "setlinewidth",
+  "solid",
+  "striped",
+  "tapered",
+  "wedged",
+];
+
+/// How far color weights may add up to past 1, so that weights such as
+/// `0.33`, `0.56` and `0.11` are not rejected over rounding errors. Graphviz
+/// uses the same tolerance.
+const WEIGHT_TOLERANCE: f64 = 1e-5;
+
+/// A single arrowhead shape, with its modifiers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Arrow {
+  /// Whether the shape is drawn open, written as an `o` prefix.
+  pub open: bool,
+  pub shape: ArrowShape,
+  /// Whether only one half of the shape is drawn, written as an `l` or `r`
+  /// prefix.
+  pub side: Option<ArrowSide>,
+}
+
+impl Display for Arrow {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    if self.open {
+      write!(f, "o")?;
+    }
+
+    match self.side {
+      Some(ArrowSide::Left) => write!(f, "l")?,
+      Some(ArrowSide::Right) => write!(f, "r")?,
+      None => {}
+    }
+
+    write!(f, "{}", self.shape)
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ArrowShape {
+  Box,
+  Crow,
+  Curve,
+  Diamond,
+  Dot,
+  ICurve,
+  Inv,
+  None,
+  Normal,
+  Tee,
+  Vee,
+}
+
+impl ArrowShape {
+  fn from_name(name: &str) -> Option<Self> {
+    Some(match name {
+      "box" => Self::Box,
+      "crow" => Self::Crow,
+      "curve" => Self::Curve,
+      "diamond" => Self::Diamond,
+      "dot" => Self::Dot,
+      "icurve" => Self::ICurve,
+      "inv" => Self::Inv,
+      "none" => Self::None,
+      "normal" => Self::Normal,
+      "tee" => Self::Tee,
+      "vee" => Self::Vee,
+      _ => return None,
+    })
+  }
+}
+
+impl Display for ArrowShape {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{}",
+      match self {
+        Self::Box => "box",
+        Self::Crow => "crow",
+        Self::Curve => "curve",
+        Self::Diamond => "diamond",
+        Self::Dot => "dot",
+        Self::ICurve => "icurve",
+        Self::Inv => "inv",
+        Self::None => "none",
+        Self::Normal => "normal",
+        Self::Tee => "tee",
+        Self::Vee => "vee",
+      }
+    )
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ArrowSide {
+  Left,
+  Right,
+}
+
+/// The value of `arrowhead` and `arrowtail`: up to four arrow shapes, drawn
+/// from the end of the edge inwards.
+///
+/// Legacy arrow names like `empty` or `open` are accepted and translated to
+/// the arrows they stand for.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArrowType {
+  pub arrows: Vec<Arrow>,
+}
+
+impl Display for ArrowType {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    for arrow in &self.arrows {
+      write!(f, "{arrow}")?;
+    }
+
+    Ok(())
+  }
+}
+
+impl Value<'_> for ArrowType {
+  fn parse(value: &str) -> Result<Self, ValueError> {
+    if let Some((_, arrows)) =
+      LEGACY_ARROW_TYPES.iter().find(|(name, _)| *name == value)
+    {
+      return Self::parse(arrows);
+    }
+
+    if value.is_empty() {
+      return Err(ValueError::new("expected an arrow shape", 0..0));
+    }
+
+    let mut arrows = Vec::new();
+    let mut position = 0;
+
+    while position < value.len() {
+      let start = position;
+
+      let open = value[position..].starts_with('o');
+
+      if open {
+        position += 1;
+      }
+
+      let side = match value[position..].chars().next() {
+        Some('l') => Some(ArrowSide::Left),
+        Some('r') => Some(ArrowSide::Right),
+        _ => None,
+      };
+
+      if side.is_some() {
+        position += 1;
+      }
+
+      let rest = &value[position..];
+
+      let Some(name) = ARROW_SHAPES.iter().find(|name| rest.starts_with(*name))
+      else {
+        let unknown = rest
+          .find(|c: char| !c.is_ascii_alphabetic())
+          .map_or(rest, |end| &rest[..end]);
+
+        let message = match suggest(unknown, ARROW_SHAPES.iter().copied()) {
+          Some(suggestion) => format!(
+            "unknown arrow shape `{unknown}`, did you mean `{suggestion}`?"
+          ),
+          None if unknown.is_empty() => "expected an arrow shape".into(),
+          None => format!("unknown arrow shape `{unknown}`"),
+        };
+
+        return Err(ValueError::new(
+          message,
+          position..position + unknown.len(),
+        ));
+      };
+
+      position += name.len();
+
+      if arrows.len() == MAX_ARROW_SHAPES {
+        return Err(ValueError::new(
+          format!("an arrow can combine at most {MAX_ARROW_SHAPES} shapes"),
+          start..value.len(),
+        ));
+      }
+
+      arrows.push(Arrow {
+        open,
+        shape: ArrowShape::from_name(name).unwrap(),
+        side,
+      });
+    }
+
+    Ok(Self { arrows })
+  }
+}
+
+/// A color, as accepted by `color`, `fillcolor`, `fontcolor` and friends.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Color<'a> {
+  /// A color given as hue, saturation and value, each between 0 and 1, such
+  /// as `0.5 0.3 1`.
+  Hsv {
+    alpha: Option<f64>,
+    hue: f64,
+    saturation: f64,
+    value: f64,
+  },
+  /// A color given by name, such as `red` or `/svg/coral`, optionally
+  /// qualified with the color scheme the name belongs to.
+  Named {
+    name: &'a str,
+    scheme: Option<&'a str>,
+  },
+  /// A color given in hexadecimal, such as `#ff0000` or `#ff000080`.
+  Rgb {
+    alpha: Option<u8>,
+    blue: u8,
+    green: u8,
+    red: u8,
+  },
+}
+
+impl Color<'_> {
+  /// The red, green, blue and alpha components of this color.
+  ///
+  /// Names are looked up in the `x11` and `svg` color schemes, with unqualified
+  /// names looked up in `x11`, as Graphviz does by default. Returns `None` for
+  /// names that are not in those schemes, such as colors from the Brewer
+  /// schemes.
+  pub fn rgba(&self) -> Option<[u8; 4]> {
+    match *self {
+      Self::Hsv {
+        alpha,
+        hue,
+        saturation,
+        value,
+      } => {
+        let sector = (hue * 6.0).floor();
+        let fraction = hue * 6.0 - sector;
+
+        let p = value * (1.0 - saturation);
+        let q = value * (1.0 - saturation * fraction);
+        let t = value * (1.0 - saturation * (1.0 - fraction));
+
+        let (red, green, blue) = match sector.rem_euclid(6.0) {
+          0.0 => (value, t, p),
+          1.0 => (q, value, p),
+          2.0 => (p, value, t),
+          3.0 => (p, q, value),
+          4.0 => (t, p, value),
+          _ => (value, p, q),
+        };
+
+        Some([
+          channel(red),
+          channel(green),
+          channel(blue),
+          channel(alpha.unwrap_or(1.0)),
+        ])
+      }
+      Self::Named { name, scheme } => {
+        if name.eq_ignore_ascii_case("transparent") {
+          return Some([255, 255, 254, 0]);
+        }
+
+        let table = match scheme {
+          None => X11,
+          Some(scheme) if scheme.eq_ignore_ascii_case("x11") => X11,
+          Some(scheme) if scheme.eq_ignore_ascii_case("svg") => SVG,
+          Some(_) => return None,
+        };
+
+        let name = name.to_ascii_lowercase();
+
+        table
+          .binary_search_by_key(&name.as_str(), |(name, _)| name)
+          .ok()
+          .map(|index| {
+            let [red, green, blue] = table[index].1;
+            [red, green, blue, 255]
+          })
+      }
+      Self::Rgb {
+        alpha,
+        blue,
+        green,
+        red,
+      } => Some([red, green, blue, alpha.unwrap_or(255)]),
+    }
+  }
+}
+
+impl Display for Color<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Hsv {
+        alpha,
+        hue,
+        saturation,
+        value,
+      } => {
+        write!(f, "{hue},{saturation},{value}")?;
+
+        if let Some(alpha) = alpha {
+          write!(f, ",{alpha}")?;
+        }
+
+        Ok(())
+      }
+      Self::Named { name, scheme } => match scheme {
+        Some(scheme) => write!(f, "/{scheme}/{name}"),
+        None => write!(f, "{name}"),
+      },
+      Self::Rgb {
+        alpha,
+        blue,
+        green,
+        red,
+      } => {
+        write!(f, "#{red:02x}{green:02x}{blue:02x}")?;
+
+        if let Some(alpha) = alpha {
+          write!(f, "{alpha:02x}")?;
+        }
+
+        Ok(())
+      }
+    }
+  }
+}
+
+impl<'a> Value<'a> for Color<'a> {
+  fn parse(value: &'a str) -> Result<Self, ValueError> {
+    if let Some(hex) = value.strip_prefix('#') {
+      if let Some((index, c)) =
+        hex.char_indices().find(|(_, c)| !c.is_ascii_hexdigit())
+      {
+        return Err(ValueError::new(
+          format!("invalid hexadecimal digit `{c}`"),
+          1 + index..1 + index + c.len_utf8(),
+        ));
+      }
+
+      if !matches!(hex.len(), 6 | 8) {
+        return Err(ValueError::new(
+          format!(
+            "expected 6 or 8 hexadecimal digits after `#`, found {}",
+            hex.len()
+          ),
+          1..value.len(),
+        ));
+      }
+
+      let byte =
+        |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).unwrap();
+
+      return Ok(Self::Rgb {
+        alpha: (hex.len() == 8).then(|| byte(6)),
+        blue: byte(4),
+        green: byte(2),
+        red: byte(0),
+      });
+    }
+
+    if value.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+      && !value.chars().all(|c| c.is_ascii_digit())
+    {
+      let components = value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|component| !component.is_empty())
+        .map(|component| {
+          let offset = offset(value, component);
+
+          let number =
+            number(component).map_err(|error| error.offset(offset))?;
+
+          if (0.0..=1.0).contains(&number) {
+            Ok(number)
+          } else {
+            Err(ValueError::new(
+              "HSV components must be between 0 and 1",
+              offset..offset + component.len(),
+            ))
+          }
+        })
+        .collect::<Result<Vec<f64>, ValueError>>()?;
+
+      return match components[..] {
+        [hue, saturation, value] => Ok(Self::Hsv {
+          alpha: None,
+          hue,
+          saturation,
+          value,
+        }),
+        [hue, saturation, value, alpha] => Ok(Self::Hsv {
+          alpha: Some(alpha),
+          hue,
+          saturation,
+          value,
+        }),
+        _ => Err(ValueError::new(
+          "expected an HSV color of the form `H,S,V` or `H,S,V,A`",
+          0..value.len(),
+        )),
+      };
+    }
+
+    let (scheme, name) = match value.strip_prefix('/') {
+      Some(rest) => {
+        let Some((scheme, name)) = rest.split_once('/') else {
+          return Err(ValueError::new(
+            "expected a color of the form `/scheme/name`",
+            0..value.len(),
+          ));
+        };
+
+        (Some(scheme).filter(|scheme| !scheme.is_empty()), name)
+      }
+      None => (None, value),
+    };
+
+    let start = value.len() - name.len();
+
+    if name.is_empty() {
+      return Err(ValueError::new("expected a color", start..start));
+    }
+
+    if let Some((index, c)) = name
+      .char_indices()
+      .find(|(_, c)| !c.is_ascii_alphanumeric())
+    {
+      return Err(ValueError::new(
+        format!("invalid character `{c}` in color name"),
+        start + index..start + index + c.len_utf8(),
+      ));
+    }
+
+    let color = Self::Named { name, scheme };
+
+    let table = match scheme {
+      None => Some(X11),
+      Some(scheme) if scheme.eq_ignore_ascii_case("x11") => Some(X11),
+      Some(scheme) if scheme.eq_ignore_ascii_case("svg") => Some(SVG),
+      Some(_) => None,
+    };
+
+    // Names made up of digits index into a Brewer scheme chosen with the
+    // `colorscheme` attribute, which we cannot check here.
+    if let Some(table) = table
+      && color.rgba().is_none()
+      && !name.chars().all(|c| c.is_ascii_digit())
+    {
+      let message =
+        match suggest(name, table.iter().map(|(candidate, _)| *candidate)) {
+          Some(suggestion) => {
+            format!("unknown color `{name}`, did you mean `{suggestion}`?")
+          }
+          None => format!("unknown color `{name}`"),
+        };
+
+      return Err(ValueError::new(message, start..value.len()));
+    }
+
+    Ok(color)
+  }
+}
+
+/// The value of `color` on edges and `fillcolor` on nodes and clusters: a
+/// `:` separated list of colors, each optionally followed by `;` and the
+/// fraction of the area it should cover.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorList<'a> {
+  pub colors: Vec<WeightedColor<'a>>,
+}
+
+impl Display for ColorList<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    for (index, color) in self.colors.iter().enumerate() {
+      if index > 0 {
+        write!(f, ":")?;
+      }
+
+      write!(f, "{}", color.color)?;
+
+      if let Some(weight) = color.weight {
+        write!(f, ";{weight}")?;
+      }
+    }
+
+    Ok(())
+  }
+}
+
+impl<'a> Value<'a> for ColorList<'a> {
+  fn parse(value: &'a str) -> Result<Self, ValueError> {
+    let mut colors = Vec::new();
+
+    let mut total = 0.0;
+
+    for item in value.split(':') {
+      let start = offset(value, item);
+
+      let (color, weight) = match item.split_once(';') {
+        Some((color, weight)) => {
+          let offset = offset(value, weight);
+
+          let number = number(weight).map_err(|error| error.offset(offset))?;
+
+          if !(0.0..=1.0).contains(&number) {
+            return Err(ValueError::new(
+              "color weights must be between 0 and 1",
+              offset..offset + weight.len(),
+            ));
+          }
+
+          total += number;
+
+          // Point at the weight that goes over, which is further into the
+          // value than any error a plain color would report.
+          if total > 1.0 + WEIGHT_TOLERANCE {
+            return Err(ValueError::new(
+              "color weights must add up to at most 1",
+              offset..offset + weight.len(),
+            ));
+          }
+
+          (color, Some(number))
+        }
+        None => (item, None),
+      };
+
+      colors.push(WeightedColor {
+        color: Color::parse(color).map_err(|error| error.offset(start))?,
+        weight,
+      });
+    }
+
+    Ok(Self { colors })
+  }
+}
+
+/// A compass point, naming a side or corner of a node.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Compass {
+  /// `c`, the center of the node.
+  Center,
+  /// `_`, whichever side of the node is most appropriate.
+  Default,
+  East,
+  North,
+  NorthEast,
+  NorthWest,
+  South,
+  SouthEast,
+  SouthWest,
+  West,
+}
+
+impl Compass {
+  fn from_name(name: &str) -> Option<Self> {
+    Some(match name {
+      "_" => Self::Default,
+      "c" => Self::Center,
+      "e" => Self::East,
+      "n" => Self::North,
+      "ne" => Self::NorthEast,
+      "nw" => Self::NorthWest,
+      "s" => Self::South,
+      "se" => Self::SouthEast,
+      "sw" => Self::SouthWest,
+      "w" => Self::West,
+      _ => return None,
+    })
+  }
+}
+
+impl Display for Compass {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{}",
+      match self {
+        Self::Center => "c",
+        Self::Default => "_",
+        Self::East => "e",
+        Self::North => "n",
+        Self::NorthEast => "ne",
+        Self::NorthWest => "nw",
+        Self::South => "s",
+        Self::SouthEast => "se",
+        Self::SouthWest => "sw",
+        Self::West => "w",
+      }
+    )
+  }
+}
+
+/// A point, `x,y` or `x,y,z`, with a trailing `!` marking it as fixed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point {
+  pub fixed: bool,
+  pub x: f64,
+  pub y: f64,
+  pub z: Option<f64>,
+}
+
+impl Display for Point {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{},{}", self.x, self.y)?;
+
+    if let Some(z) = self.z {
+      write!(f, ",{z}")?;
+    }
+
+    if self.fixed {
+      write!(f, "!")?;
+    }
+
+    Ok(())
+  }
+}
+
+impl Value<'_> for Point {
+  fn parse(value: &str) -> Result<Self, ValueError> {
+    let (coordinates, fixed) = match value.strip_suffix('!') {
+      Some(coordinates) => (coordinates, true),
+      None => (value, false),
+    };
+
+    let coordinates = numbers(coordinates)?;
+
+    match coordinates[..] {
+      [x, y] => Ok(Self {
+        fixed,
+        x,
+        y,
+        z: None,
+      }),
+      [x, y, z] => Ok(Self {
+        fixed,
+        x,
+        y,
+        z: Some(z),
+      }),
+      _ => Err(ValueError::new(
+        "expected a point of the form `x,y` or `x,y,z`",
+        0..value.len(),
+      )),
+    }
+  }
+}
+
+/// The value of `headport` and `tailport`: a port name, a compass point, or
+/// both separated by `:`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PortPos<'a> {
+  pub compass: Option<Compass>,
+  pub port: Option<&'a str>,
+}
+
+impl Display for PortPos<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match (self.port, self.compass) {
+      (Some(port), Some(compass)) => write!(f, "{port}:{compass}"),
+      (Some(port), None) => write!(f, "{port}"),
+      (None, Some(compass)) => write!(f, "{compass}"),
+      (None, None) => Ok(()),
+    }
+  }
+}
+
+impl<'a> Value<'a> for PortPos<'a> {
+  fn parse(value: &'a str) -> Result<Self, ValueError> {
+    let Some((port, compass)) = value.rsplit_once(':') else {
+      return Ok(match Compass::from_name(value) {
+        Some(compass) => Self {
+          compass: Some(compass),
+          port: None,
+        },
+        None => Self {
+          compass: None,
+          port: Some(value),
+        },
+      });
+    };
+
+    let Some(compass) = Compass::from_name(compass) else {
+      let start = port.len() + 1;
+
+      let message = match suggest(compass, COMPASS_POINTS.iter().copied()) {
+        Some(suggestion) => format!(
+          "unknown compass point `{compass}`, did you mean `{suggestion}`?"
+        ),
+        None => format!("unknown compass point `{compass}`"),
+      };
+
+      return Err(ValueError::new(message, start..value.len()));
+    };
+
+    Ok(Self {
+      compass: Some(compass),
+      port: Some(port),
+    })
+  }
+}
+
+/// A rectangle, `llx,lly,urx,ury`, given by its lower left and upper right
+/// corners.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rect {
+  pub llx: f64,
+  pub lly: f64,
+  pub urx: f64,
+  pub ury: f64,
+}
+
+impl Display for Rect {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{},{},{},{}", self.llx, self.lly, self.urx, self.ury)
+  }
+}
+
+impl Value<'_> for Rect {
+  fn parse(value: &str) -> Result<Self, ValueError> {
+    match numbers(value)?[..] {
+      [llx, lly, urx, ury] => Ok(Self { llx, lly, urx, ury }),
+      _ => Err(ValueError::new(
+        "expected a rectangle of the form `llx,lly,urx,ury`",
+        0..value.len(),
+      )),
+    }
+  }
+}
+
+/// A B-spline, as found in the `pos` attribute of laid out edges.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Spline {
+  /// Where the arrowhead at the end of the edge points to, given as `e,x,y`.
+  pub end: Option<Point>,
+  /// The control points, of which there are `3n + 1`.
+  pub points: Vec<Point>,
+  /// Where the arrowhead at the start of the edge points to, given as
+  /// `s,x,y`.
+  pub start: Option<Point>,
+}
+
+impl Display for Spline {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    if let Some(end) = self.end {
+      write!(f, "e,{end} ")?;
+    }
+
+    if let Some(start) = self.start {
+      write!(f, "s,{start} ")?;
+    }
+
+    for (index, point) in self.points.iter().enumerate() {
+      if index > 0 {
+        write!(f, " ")?;
+      }
+
+      write!(f, "{point}")?;
+    }
+
+    Ok(())
+  }
+}
+
+/// The value of `pos` on laid out edges: one or more `;` separated splines.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SplineType {
+  pub splines: Vec<Spline>,
+}
+
+impl Display for SplineType {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    for (index, spline) in self.splines.iter().enumerate() {
+      if index > 0 {
+        write!(f, ";")?;
+      }
+
+      write!(f, "{spline}")?;
+    }
+
+    Ok(())
+  }
+}
+
+impl Value<'_> for SplineType {
+  fn parse(value: &str) -> Result<Self, ValueError> {
+    let mut splines = Vec::new();
+
+    for text in value.split(';') {
+      let mut spline = Spline {
+        end: None,
+        points: Vec::new(),
+        start: None,
+      };
+
+      for field in text.split_whitespace() {
+        let start = offset(value, field);
+
+        let (slot, point) =
+          match (field.strip_prefix("e,"), field.strip_prefix("s,")) {
+            (Some(point), _) if spline.points.is_empty() => {
+              (&mut spline.end, point)
+            }
+            (_, Some(point)) if spline.points.is_empty() => {
+              (&mut spline.start, point)
+            }
+            _ => {
+              spline.points.push(
+                Point::parse(field).map_err(|error| error.offset(start))?,
+              );
+              continue;
+            }
+          };
+
+        *slot =
+          Some(Point::parse(point).map_err(|error| error.offset(start + 2))?);
+      }
+
+      if spline.points.len() < 4 || spline.points.len() % 3 != 1 {
+        let start = offset(value, text);
+
+        return Err(ValueError::new(
+          format!(
+            "expected 3n + 1 control points, with n at least 1, found {}",
+            spline.points.len()
+          ),
+          start..start + text.len(),
+        ));
+      }
+
+      splines.push(spline);
+    }
+
+    Ok(Self { splines })
+  }
+}
+
+/// The value of `style`: a `,` separated list of styles, some of which take
+/// arguments in parentheses, such as `filled,setlinewidth(2)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Style<'a> {
+  pub items: Vec<StyleItem<'a>>,
+}
+
+impl Display for Style<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    for (index, item) in self.items.iter().enumerate() {
+      if index > 0 {
+        write!(f, ",")?;
+      }
+
+      write!(f, "{}", item.name)?;
+
+      if !item.arguments.is_empty() {
+        write!(f, "({})", item.arguments.join(","))?;
+      }
+    }
+
+    Ok(())
+  }
+}
+
+impl<'a> Value<'a> for Style<'a> {
+  fn parse(value: &'a str) -> Result<Self, ValueError> {
+    let mut items = Vec::new();
+    let mut position = 0;
+
+    loop {
+      let rest = &value[position..];
+
+      let name_end = rest.find(['(', ',']).unwrap_or(rest.len());
+
+      let name = rest[..name_end].trim();
+
+      let start =
+        position + rest[..name_end].len() - rest[..name_end].trim_start().len();
+
+      if name.is_empty() {
+        return Err(ValueError::new(
+          "expected a style",
+          position..position + name_end,
+        ));
+      }
+
+      if !STYLES.contains(&name) {
+        let message = match suggest(name, STYLES.iter().copied()) {
+          Some(suggestion) => {
+            format!("unknown style `{name}`, did you mean `{suggestion}`?")
+          }
+          None => format!("unknown style `{name}`"),
+        };
+
+        return Err(ValueError::new(message, start..start + name.len()));
+      }
+
+      position += name_end;
+
+      let mut arguments = Vec::new();
+
+      if value[position..].starts_with('(') {
+        let Some(close) = value[position..].find(')') else {
+          return Err(ValueError::new("unclosed `(`", position..position + 1));
+        };
+
+        arguments = value[position + 1..position + close]
+          .split(',')
+          .map(str::trim)
+          .collect();
+
+        position += close + 1;
+      }
+
+      items.push(StyleItem { arguments, name });
+
+      let rest = &value[position..];
+
+      let trimmed = rest.trim_start();
+
+      if trimmed.is_empty() {
+        break;
+      }
+
+      let separator = position + rest.len() - trimmed.len();
+
+      if !trimmed.starts_with(',') {
+        return Err(ValueError::new(
+          "expected `,` between styles",
+          separator..separator + 1,
+        ));
+      }
+
+      position = separator + 1;
+    }
+
+    Ok(Self { items })
+  }
+}
+
+/// A single style, such as `filled` or `setlinewidth(2)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StyleItem<'a> {
+  pub arguments: Vec<&'a str>,
+  pub name: &'a str,
+}
+
+/// A value type that attribute values can be parsed as.
+pub trait Value<'a>: Sized {
+  /// Parse `value`, the text of an attribute value without surrounding
+  /// quotes.
+  fn parse(value: &'a str) -> Result<Self, ValueError>;
+}
+
+impl Value<'_> for bool {
+  fn parse(value: &str) -> Result<Self, ValueError> {
+    if ["true", "yes"]
+      .iter()
+      .any(|keyword| value.eq_ignore_ascii_case(keyword))
+    {
+      return Ok(true);
+    }
+
+    if ["false", "no"]
+      .iter()
+      .any(|keyword| value.eq_ignore_ascii_case(keyword))
+    {
+      return Ok(false);
+    }
+
+    value.parse::<i64>().map(|value| value != 0).map_err(|_| {
+      ValueError::new(
+        "expected `true`, `false`, `yes`, `no` or an integer",
+        0..value.len(),
+      )
+    })
+  }
+}
+
+impl Value<'_> for f64 {
+  fn parse(value: &str) -> Result<Self, ValueError> {
+    number(value)
+  }
+}
+
+/// An error in an attribute value, with a span relative to the start of the
+/// value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValueError {
+  pub message: String,
+  pub span: Range<usize>,
+}
+
+impl ValueError {
+  pub(crate) fn new(message: impl Into<String>, span: Range<usize>) -> Self {
+    Self {
+      message: message.into(),
+      span,
+    }
+  }
+
+  /// Shift this error's span `offset` bytes to the right.
+  pub(crate) fn offset(self, offset: usize) -> Self {
+    Self {
+      message: self.message,
+      span: self.span.start + offset..self.span.end + offset,
+    }
+  }
+}
+
+/// A color in a [`ColorList`], with the fraction of the area it covers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeightedColor<'a> {
+  pub color: Color<'a>,
+  pub weight: Option<f64>,
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn channel(value: f64) -> u8 {
+  (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+pub(crate) fn number(text: &str) -> Result<f64, ValueError> {
+  let trimmed = text.trim();
+
+  let start = text.len() - text.trim_start().len();
+
+  match trimmed.parse::<f64>() {
+    Ok(number) if number.is_finite() => Ok(number),
+    _ if trimmed.is_empty() => {
+      Err(ValueError::new("expected a number", 0..text.len()))
+    }
+    _ => Err(ValueError::new(
+      format!("expected a number, found `{trimmed}`"),
+      start..start + trimmed.len(),
+    )),
+  }
+}
+
+/// Parse the `,` separated numbers in `text`.
+fn numbers(text: &str) -> Result<Vec<f64>, ValueError> {
+  text
+    .split(',')
+    .map(|field| {
+      number(field).map_err(|error| error.offset(offset(text, field)))
+    })
+    .collect()
+}
+
+/// The byte offset of `inner`, a slice of `outer`, from the start of `outer`.
+pub(crate) fn offset(outer: &str, inner: &str) -> usize {
+  inner.as_ptr() as usize - outer.as_ptr() as usize
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, std::fmt::Debug};
+
+  #[test]
+  fn arrow_types() {
+    assert_eq!(
+      ArrowType::parse("olbox").unwrap().arrows,
+      vec![Arrow {
+        open: true,
+        shape: ArrowShape::Box,
+        side: Some(ArrowSide::Left),
+      }],
+    );
+
+    assert_eq!(ArrowType::parse("empty").unwrap().to_string(), "onormal");
+    assert_eq!(ArrowType::parse("invodot").unwrap().arrows.len(), 2);
+
+    round_trip::<ArrowType>("lteeoldiamond");
+
+    error::<ArrowType>(
+      "normalbx",
+      "unknown arrow shape `bx`, did you mean `box`?",
+      "bx",
+    );
+    error::<ArrowType>(
+      "dotdotdotdotdot",
+      "an arrow can combine at most 4 shapes",
+      "dot",
+    );
+    error::<ArrowType>("o", "expected an arrow shape", "");
+  }
+
+  #[test]
+  fn bools() {
+    assert!(bool::parse("TRUE").unwrap());
+    assert!(bool::parse("1").unwrap());
+    assert!(!bool::parse("no").unwrap());
+    assert!(bool::parse("maybe").is_err());
+  }
+
+  #[test]
+  fn color_lists() {
+    let list = ColorList::parse("red:blue;0.3:#00ff00").unwrap();
+
+    assert_eq!(list.colors.len(), 3);
+    assert_eq!(list.colors[1].weight, Some(0.3));
+
+    round_trip::<ColorList>("red:blue;0.3:#00ff00");
+
+    error::<ColorList>(
+      "red:blu",
+      "unknown color `blu`, did you mean `blue`?",
+      "blu",
+    );
+    error::<ColorList>("red;2", "color weights must be between 0 and 1", "2");
+    error::<ColorList>("red;x", "expected a number, found `x`", "x");
+    error::<ColorList>(
+      "red;0.6:blue;0.6",
+      "color weights must add up to at most 1",
+      "0.6",
+    );
+
+    let list = ColorList::parse("red;0.33:blue;0.56:green;0.11").unwrap();
+
+    assert_eq!(list.colors.len(), 3);
+  }
+
+  #[test]
+  fn colors() {
+    assert_eq!(
+      Color::parse("#ff000080").unwrap(),
+      Color::Rgb {
+        alpha: Some(0x80),
+        blue: 0,
+        green: 0,
+        red: 0xff,
+      },
+    );
+
+    assert_eq!(Color::parse("red").unwrap().rgba(), Some([255, 0, 0, 255]));
+    assert_eq!(Color::parse("Red").unwrap().rgba(), Some([255, 0, 0, 255]));
+    assert_eq!(
+      Color::parse("/svg/green").unwrap().rgba(),
+      Some([0, 128, 0, 255]),
+    );
+    assert_eq!(
+      Color::parse("/x11/green").unwrap().rgba(),
+      Some([0, 255, 0, 255]),
+    );
+    assert_eq!(
+      Color::parse("0.0 1.0 1.0").unwrap().rgba(),
+      Some([255, 0, 0, 255]),
+    );
+    assert_eq!(
+      Color::parse("0.5,1,1,0").unwrap().rgba(),
+      Some([0, 255, 255, 0]),
+    );
+    assert_eq!(Color::parse("/blues9/3").unwrap().rgba(), None);
+    assert_eq!(Color::parse("3").unwrap().rgba(), None);
+
+    round_trip::<Color>("#ff0000");
+    round_trip::<Color>("/svg/coral");
+    round_trip::<Color>("0.5,0.25,1");
+
+    error::<Color>("#ff00zz", "invalid hexadecimal digit `z`", "z");
+    error::<Color>(
+      "#ff00",
+      "expected 6 or 8 hexadecimal digits after `#`, found 4",
+      "ff00",
+    );
+    error::<Color>("redd", "unknown color `redd`, did you mean `red`?", "redd");
+    error::<Color>(
+      "/svg/lightgoldenrod",
+      "unknown color `lightgoldenrod`, did you mean `lightgoldenrodyellow`?",
+      "lightgoldenrod",
+    );
+    error::<Color>("0.5 2 1", "HSV components must be between 0 and 1", "2");
+    error::<Color>(
+      "0.5 1",
+      "expected an HSV color of the form `H,S,V` or `H,S,V,A`",
+      "0.5 1",
+    );
+    error::<Color>("light blue", "invalid character ` ` in color name", " ");
+    error::<Color>(
+      "/svg",
+      "expected a color of the form `/scheme/name`",
+      "/svg",
+    );
+    error::<Color>("//", "expected a color", "");
+  }
+
+  #[track_caller]
+  fn error<'a, T: Value<'a> + Debug>(
+    value: &'a str,
+    message: &str,
+    span: &str,
+  ) {
+    let error = T::parse(value).unwrap_err();
+
+    assert_eq!(error.message, message);
+    assert_eq!(&value[error.span], span);
+  }
+
+  #[test]
+  fn ids() {
+    let src = r##"graph { a [color="#ff00zz", pos="1,x"] }"##;
+
+    let graph = parse(src).unwrap();
+
+    let Statement::Node(node) = &graph.statements[0] else {
+      panic!();
+    };
+
+    let error = node.attributes[0]
+      .value
+      .as_ref()
+      .unwrap()
+      .parse::<Color>()
+      .unwrap_err();
+
+    assert_eq!(&src[error.span], "z");
+
+    let error = node.attributes[1]
+      .value
+      .as_ref()
+      .unwrap()
+      .parse::<Point>()
+      .unwrap_err();
+
+    assert_eq!(&src[error.span], "x");
+  }
+
+  #[test]
+  fn points() {
+    assert_eq!(
+      Point::parse("0.5,1.2!").unwrap(),
+      Point {
+        fixed: true,
+        x: 0.5,
+        y: 1.2,
+        z: None,
+      },
+    );
+
+    round_trip::<Point>("1,2,3");
+    round_trip::<Point>("-1.5,2!");
+
+    error::<Point>("1", "expected a point of the form `x,y` or `x,y,z`", "1");
+    error::<Point>("1,", "expected a number", "");
+    error::<Point>("1, y", "expected a number, found `y`", "y");
+  }
+
+  #[test]
+  fn port_positions() {
+    assert_eq!(
+      PortPos::parse("ne").unwrap(),
+      PortPos {
+        compass: Some(Compass::NorthEast),
+        port: None,
+      },
+    );
+
+    round_trip::<PortPos>("port");
+    round_trip::<PortPos>("port:sw");
+
+    error::<PortPos>(
+      "port:nne",
+      "unknown compass point `nne`, did you mean `ne`?",
+      "nne",
+    );
+  }
+
+  #[test]
+  fn rects() {
+    round_trip::<Rect>("0,0,10.5,20");
+
+    error::<Rect>(
+      "0,0,10",
+      "expected a rectangle of the form `llx,lly,urx,ury`",
+      "0,0,10",
+    );
+  }
+
+  #[track_caller]
+  fn round_trip<'a, T: Value<'a> + Debug + Display>(value: &'a str) {
+    assert_eq!(T::parse(value).unwrap().to_string(), value);
+  }
+
+  #[test]
+  fn spline_types() {
+    let splines =
+      SplineType::parse("e,4,4 0,0 1,1 2,2 3,3;0,0 1,1 2,2 3,3 4,4 5,5 6,6")
+        .unwrap();
+
+    assert_eq!(splines.splines.len(), 2);
+    assert!(splines.splines[0].end.is_some());
+    assert_eq!(splines.splines[1].points.len(), 7);
+
+    round_trip::<SplineType>("s,1,1 0,0 1,1 2,2 3,3");
+
+    error::<SplineType>(
+      "0,0 1,1",
+      "expected 3n + 1 control points, with n at least 1, found 2",
+      "0,0 1,1",
+    );
+    error::<SplineType>("e,x,1 0,0", "expected a number, found `x`", "x");
+  }
+
+  #[test]
+  fn styles() {
+    assert_eq!(
+      Style::parse("filled, setlinewidth(2)").unwrap().items,
+      vec![
+        StyleItem {
+          arguments: Vec::new(),
+          name: "filled",
+        },
+        StyleItem {
+          arguments: vec!["2"],
+          name: "setlinewidth",
+        },
+      ],
+    );
+
+    round_trip::<Style>("filled,rounded");
+
+    error::<Style>(
+      "filled,rouned",
+      "unknown style `rouned`, did you mean `rounded`?",
+      "rouned",
+    );
+    error::<Style>("setlinewidth(2", "unclosed `(`", "(");
+    error::<Style>("filled,", "expected a style", "");
+    error::<Style>("bold(1) dashed", "expected `,` between styles", "d");
+  }
+}
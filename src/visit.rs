@@ -0,0 +1,734 @@
+//! Traversals of the [`Graph`] syntax tree.
+//!
+//! [`Visit`] walks a tree by reference, [`VisitMut`] by mutable reference and
+//! [`Fold`] by value, rebuilding it from the results. Each trait has one
+//! method per syntax tree type, whose default implementation calls the
+//! function of the same name in this module to walk the node's children.
+//! Implementations override the methods for the nodes they are interested in,
+//! and call that function from the override to keep walking into children.
+//!
+//! Every node is reached, including subgraphs used as edge targets, such as
+//! `{ b c }` in `a -> { b c }`. Children are walked in source order, and
+//! identifiers, including attribute keys and values, are visited as [`Id`]s.
+//!
+//! # Examples
+//!
+//! ```
+//! use dotgraph::{NodeId, visit::Visit};
+//!
+//! #[derive(Default)]
+//! struct Nodes(Vec<String>);
+//!
+//! impl Visit<'_, '_> for Nodes {
+//!   fn visit_node_id(&mut self, node_id: &NodeId) {
+//!     self.0.push(node_id.id.decoded().into_owned());
+//!   }
+//! }
+//!
+//! let graph = dotgraph::parse("digraph { a -> { b subgraph { c } } }").unwrap();
+//!
+//! let mut nodes = Nodes::default();
+//! nodes.visit_graph(&graph);
+//!
+//! assert_eq!(nodes.0, ["a", "b", "c"]);
+//! ```
+
+use super::*;
+
+/// A traversal that takes a syntax tree by value and rebuilds it, so that
+/// nodes can be replaced.
+pub trait Fold<'src> {
+  fn fold_attribute(&mut self, attribute: Attribute<'src>) -> Attribute<'src> {
+    fold_attribute(self, attribute)
+  }
+
+  fn fold_attribute_statement(
+    &mut self,
+    statement: AttributeStatement<'src>,
+  ) -> AttributeStatement<'src> {
+    fold_attribute_statement(self, statement)
+  }
+
+  fn fold_edge(&mut self, edge: Edge<'src>) -> Edge<'src> {
+    fold_edge(self, edge)
+  }
+
+  fn fold_edge_statement(
+    &mut self,
+    statement: EdgeStatement<'src>,
+  ) -> EdgeStatement<'src> {
+    fold_edge_statement(self, statement)
+  }
+
+  fn fold_edge_target(&mut self, target: EdgeTarget<'src>) -> EdgeTarget<'src> {
+    fold_edge_target(self, target)
+  }
+
+  fn fold_graph(&mut self, graph: Graph<'src>) -> Graph<'src> {
+    fold_graph(self, graph)
+  }
+
+  fn fold_id(&mut self, id: Id<'src>) -> Id<'src> {
+    id
+  }
+
+  fn fold_node_id(&mut self, node_id: NodeId<'src>) -> NodeId<'src> {
+    fold_node_id(self, node_id)
+  }
+
+  fn fold_node_statement(
+    &mut self,
+    statement: NodeStatement<'src>,
+  ) -> NodeStatement<'src> {
+    fold_node_statement(self, statement)
+  }
+
+  fn fold_port(&mut self, port: Port<'src>) -> Port<'src> {
+    fold_port(self, port)
+  }
+
+  fn fold_statement(&mut self, statement: Statement<'src>) -> Statement<'src> {
+    fold_statement(self, statement)
+  }
+
+  fn fold_subgraph(&mut self, subgraph: Subgraph<'src>) -> Subgraph<'src> {
+    fold_subgraph(self, subgraph)
+  }
+}
+
+/// A traversal that borrows a syntax tree.
+///
+/// `'ast` is the lifetime of the borrow, so that a visitor can keep references
+/// to the nodes it visits, and `'src` is the lifetime of the source the tree
+/// was parsed from.
+pub trait Visit<'ast, 'src> {
+  fn visit_attribute(&mut self, attribute: &'ast Attribute<'src>) {
+    visit_attribute(self, attribute);
+  }
+
+  fn visit_attribute_statement(
+    &mut self,
+    statement: &'ast AttributeStatement<'src>,
+  ) {
+    visit_attribute_statement(self, statement);
+  }
+
+  fn visit_edge(&mut self, edge: &'ast Edge<'src>) {
+    visit_edge(self, edge);
+  }
+
+  fn visit_edge_statement(&mut self, statement: &'ast EdgeStatement<'src>) {
+    visit_edge_statement(self, statement);
+  }
+
+  fn visit_edge_target(&mut self, target: &'ast EdgeTarget<'src>) {
+    visit_edge_target(self, target);
+  }
+
+  fn visit_graph(&mut self, graph: &'ast Graph<'src>) {
+    visit_graph(self, graph);
+  }
+
+  fn visit_id(&mut self, _id: &'ast Id<'src>) {}
+
+  fn visit_node_id(&mut self, node_id: &'ast NodeId<'src>) {
+    visit_node_id(self, node_id);
+  }
+
+  fn visit_node_statement(&mut self, statement: &'ast NodeStatement<'src>) {
+    visit_node_statement(self, statement);
+  }
+
+  fn visit_port(&mut self, port: &'ast Port<'src>) {
+    visit_port(self, port);
+  }
+
+  fn visit_statement(&mut self, statement: &'ast Statement<'src>) {
+    visit_statement(self, statement);
+  }
+
+  fn visit_subgraph(&mut self, subgraph: &'ast Subgraph<'src>) {
+    visit_subgraph(self, subgraph);
+  }
+}
+
+/// A traversal that mutably borrows a syntax tree, so that nodes can be
+/// edited in place.
+pub trait VisitMut<'src> {
+  fn visit_attribute_mut(&mut self, attribute: &mut Attribute<'src>) {
+    visit_attribute_mut(self, attribute);
+  }
+
+  fn visit_attribute_statement_mut(
+    &mut self,
+    statement: &mut AttributeStatement<'src>,
+  ) {
+    visit_attribute_statement_mut(self, statement);
+  }
+
+  fn visit_edge_mut(&mut self, edge: &mut Edge<'src>) {
+    visit_edge_mut(self, edge);
+  }
+
+  fn visit_edge_statement_mut(&mut self, statement: &mut EdgeStatement<'src>) {
+    visit_edge_statement_mut(self, statement);
+  }
+
+  fn visit_edge_target_mut(&mut self, target: &mut EdgeTarget<'src>) {
+    visit_edge_target_mut(self, target);
+  }
+
+  fn visit_graph_mut(&mut self, graph: &mut Graph<'src>) {
+    visit_graph_mut(self, graph);
+  }
+
+  fn visit_id_mut(&mut self, _id: &mut Id<'src>) {}
+
+  fn visit_node_id_mut(&mut self, node_id: &mut NodeId<'src>) {
+    visit_node_id_mut(self, node_id);
+  }
+
+  fn visit_node_statement_mut(&mut self, statement: &mut NodeStatement<'src>) {
+    visit_node_statement_mut(self, statement);
+  }
+
+  fn visit_port_mut(&mut self, port: &mut Port<'src>) {
+    visit_port_mut(self, port);
+  }
+
+  fn visit_statement_mut(&mut self, statement: &mut Statement<'src>) {
+    visit_statement_mut(self, statement);
+  }
+
+  fn visit_subgraph_mut(&mut self, subgraph: &mut Subgraph<'src>) {
+    visit_subgraph_mut(self, subgraph);
+  }
+}
+
+pub fn fold_attribute<'src, F: Fold<'src> + ?Sized>(
+  folder: &mut F,
+  attribute: Attribute<'src>,
+) -> Attribute<'src> {
+  Attribute {
+    key: folder.fold_id(attribute.key),
+    span: attribute.span,
+    value: attribute.value.map(|value| folder.fold_id(value)),
+  }
+}
+
+pub fn fold_attribute_statement<'src, F: Fold<'src> + ?Sized>(
+  folder: &mut F,
+  statement: AttributeStatement<'src>,
+) -> AttributeStatement<'src> {
+  AttributeStatement {
+    attributes: fold_attributes(folder, statement.attributes),
+    span: statement.span,
+    target: statement.target,
+  }
+}
+
+fn fold_attributes<'src, F: Fold<'src> + ?Sized>(
+  folder: &mut F,
+  attributes: Vec<Attribute<'src>>,
+) -> Vec<Attribute<'src>> {
+  attributes
+    .into_iter()
+    .map(|attribute| folder.fold_attribute(attribute))
+    .collect()
+}
+
+pub fn fold_edge<'src, F: Fold<'src> + ?Sized>(
+  folder: &mut F,
+  edge: Edge<'src>,
+) -> Edge<'src> {
+  Edge {
+    operation: edge.operation,
+    span: edge.span,
+    target: folder.fold_edge_target(edge.target),
+  }
+}
+
+pub fn fold_edge_statement<'src, F: Fold<'src> + ?Sized>(
+  folder: &mut F,
+  statement: EdgeStatement<'src>,
+) -> EdgeStatement<'src> {
+  let from = folder.fold_edge_target(statement.from);
+
+  let edges = statement
+    .edges
+    .into_iter()
+    .map(|edge| folder.fold_edge(edge))
+    .collect();
+
+  EdgeStatement {
+    attributes: fold_attributes(folder, statement.attributes),
+    edges,
+    from,
+    span: statement.span,
+  }
+}
+
+pub fn fold_edge_target<'src, F: Fold<'src> + ?Sized>(
+  folder: &mut F,
+  target: EdgeTarget<'src>,
+) -> EdgeTarget<'src> {
+  match target {
+    EdgeTarget::NodeId(node_id) => {
+      EdgeTarget::NodeId(folder.fold_node_id(node_id))
+    }
+    EdgeTarget::Subgraph(subgraph) => {
+      EdgeTarget::Subgraph(folder.fold_subgraph(subgraph))
+    }
+  }
+}
+
+pub fn fold_graph<'src, F: Fold<'src> + ?Sized>(
+  folder: &mut F,
+  graph: Graph<'src>,
+) -> Graph<'src> {
+  Graph {
+    id: graph.id.map(|id| folder.fold_id(id)),
+    kind: graph.kind,
+    span: graph.span,
+    statements: fold_statements(folder, graph.statements),
+    strict: graph.strict,
+  }
+}
+
+pub fn fold_node_id<'src, F: Fold<'src> + ?Sized>(
+  folder: &mut F,
+  node_id: NodeId<'src>,
+) -> NodeId<'src> {
+  NodeId {
+    id: folder.fold_id(node_id.id),
+    port: node_id.port.map(|port| folder.fold_port(port)),
+    span: node_id.span,
+  }
+}
+
+pub fn fold_node_statement<'src, F: Fold<'src> + ?Sized>(
+  folder: &mut F,
+  statement: NodeStatement<'src>,
+) -> NodeStatement<'src> {
+  NodeStatement {
+    id: folder.fold_node_id(statement.id),
+    attributes: fold_attributes(folder, statement.attributes),
+    span: statement.span,
+  }
+}
+
+pub fn fold_port<'src, F: Fold<'src> + ?Sized>(
+  folder: &mut F,
+  port: Port<'src>,
+) -> Port<'src> {
+  Port {
+    id: folder.fold_id(port.id),
+    compass: port.compass.map(|compass| folder.fold_id(compass)),
+    span: port.span,
+  }
+}
+
+pub fn fold_statement<'src, F: Fold<'src> + ?Sized>(
+  folder: &mut F,
+  statement: Statement<'src>,
+) -> Statement<'src> {
+  match statement {
+    Statement::Assign(key, value) => {
+      let key = folder.fold_id(key);
+      Statement::Assign(key, folder.fold_id(value))
+    }
+    Statement::Attr(statement) => {
+      Statement::Attr(folder.fold_attribute_statement(statement))
+    }
+    Statement::Edge(statement) => {
+      Statement::Edge(folder.fold_edge_statement(statement))
+    }
+    Statement::Node(statement) => {
+      Statement::Node(folder.fold_node_statement(statement))
+    }
+    Statement::Subgraph(subgraph) => {
+      Statement::Subgraph(folder.fold_subgraph(subgraph))
+    }
+  }
+}
+
+fn fold_statements<'src, F: Fold<'src> + ?Sized>(
+  folder: &mut F,
+  statements: Vec<Statement<'src>>,
+) -> Vec<Statement<'src>> {
+  statements
+    .into_iter()
+    .map(|statement| folder.fold_statement(statement))
+    .collect()
+}
+
+pub fn fold_subgraph<'src, F: Fold<'src> + ?Sized>(
+  folder: &mut F,
+  subgraph: Subgraph<'src>,
+) -> Subgraph<'src> {
+  Subgraph {
+    id: subgraph.id.map(|id| folder.fold_id(id)),
+    span: subgraph.span,
+    statements: fold_statements(folder, subgraph.statements),
+  }
+}
+
+pub fn visit_attribute<'ast, 'src, V: Visit<'ast, 'src> + ?Sized>(
+  visitor: &mut V,
+  attribute: &'ast Attribute<'src>,
+) {
+  visitor.visit_id(&attribute.key);
+
+  if let Some(value) = &attribute.value {
+    visitor.visit_id(value);
+  }
+}
+
+pub fn visit_attribute_mut<'src, V: VisitMut<'src> + ?Sized>(
+  visitor: &mut V,
+  attribute: &mut Attribute<'src>,
+) {
+  visitor.visit_id_mut(&mut attribute.key);
+
+  if let Some(value) = &mut attribute.value {
+    visitor.visit_id_mut(value);
+  }
+}
+
+pub fn visit_attribute_statement<'ast, 'src, V: Visit<'ast, 'src> + ?Sized>(
+  visitor: &mut V,
+  statement: &'ast AttributeStatement<'src>,
+) {
+  for attribute in &statement.attributes {
+    visitor.visit_attribute(attribute);
+  }
+}
+
+pub fn visit_attribute_statement_mut<'src, V: VisitMut<'src> + ?Sized>(
+  visitor: &mut V,
+  statement: &mut AttributeStatement<'src>,
+) {
+  for attribute in &mut statement.attributes {
+    visitor.visit_attribute_mut(attribute);
+  }
+}
+
+pub fn visit_edge<'ast, 'src, V: Visit<'ast, 'src> + ?Sized>(
+  visitor: &mut V,
+  edge: &'ast Edge<'src>,
+) {
+  visitor.visit_edge_target(&edge.target);
+}
+
+pub fn visit_edge_mut<'src, V: VisitMut<'src> + ?Sized>(
+  visitor: &mut V,
+  edge: &mut Edge<'src>,
+) {
+  visitor.visit_edge_target_mut(&mut edge.target);
+}
+
+pub fn visit_edge_statement<'ast, 'src, V: Visit<'ast, 'src> + ?Sized>(
+  visitor: &mut V,
+  statement: &'ast EdgeStatement<'src>,
+) {
+  visitor.visit_edge_target(&statement.from);
+
+  for edge in &statement.edges {
+    visitor.visit_edge(edge);
+  }
+
+  for attribute in &statement.attributes {
+    visitor.visit_attribute(attribute);
+  }
+}
+
+pub fn visit_edge_statement_mut<'src, V: VisitMut<'src> + ?Sized>(
+  visitor: &mut V,
+  statement: &mut EdgeStatement<'src>,
+) {
+  visitor.visit_edge_target_mut(&mut statement.from);
+
+  for edge in &mut statement.edges {
+    visitor.visit_edge_mut(edge);
+  }
+
+  for attribute in &mut statement.attributes {
+    visitor.visit_attribute_mut(attribute);
+  }
+}
+
+pub fn visit_edge_target<'ast, 'src, V: Visit<'ast, 'src> + ?Sized>(
+  visitor: &mut V,
+  target: &'ast EdgeTarget<'src>,
+) {
+  match target {
+    EdgeTarget::NodeId(node_id) => visitor.visit_node_id(node_id),
+    EdgeTarget::Subgraph(subgraph) => visitor.visit_subgraph(subgraph),
+  }
+}
+
+pub fn visit_edge_target_mut<'src, V: VisitMut<'src> + ?Sized>(
+  visitor: &mut V,
+  target: &mut EdgeTarget<'src>,
+) {
+  match target {
+    EdgeTarget::NodeId(node_id) => visitor.visit_node_id_mut(node_id),
+    EdgeTarget::Subgraph(subgraph) => visitor.visit_subgraph_mut(subgraph),
+  }
+}
+
+pub fn visit_graph<'ast, 'src, V: Visit<'ast, 'src> + ?Sized>(
+  visitor: &mut V,
+  graph: &'ast Graph<'src>,
+) {
+  if let Some(id) = &graph.id {
+    visitor.visit_id(id);
+  }
+
+  for statement in &graph.statements {
+    visitor.visit_statement(statement);
+  }
+}
+
+pub fn visit_graph_mut<'src, V: VisitMut<'src> + ?Sized>(
+  visitor: &mut V,
+  graph: &mut Graph<'src>,
+) {
+  if let Some(id) = &mut graph.id {
+    visitor.visit_id_mut(id);
+  }
+
+  for statement in &mut graph.statements {
+    visitor.visit_statement_mut(statement);
+  }
+}
+
+pub fn visit_node_id<'ast, 'src, V: Visit<'ast, 'src> + ?Sized>(
+  visitor: &mut V,
+  node_id: &'ast NodeId<'src>,
+) {
+  visitor.visit_id(&node_id.id);
+
+  if let Some(port) = &node_id.port {
+    visitor.visit_port(port);
+  }
+}
+
+pub fn visit_node_id_mut<'src, V: VisitMut<'src> + ?Sized>(
+  visitor: &mut V,
+  node_id: &mut NodeId<'src>,
+) {
+  visitor.visit_id_mut(&mut node_id.id);
+
+  if let Some(port) = &mut node_id.port {
+    visitor.visit_port_mut(port);
+  }
+}
+
+pub fn visit_node_statement<'ast, 'src, V: Visit<'ast, 'src> + ?Sized>(
+  visitor: &mut V,
+  statement: &'ast NodeStatement<'src>,
+) {
+  visitor.visit_node_id(&statement.id);
+
+  for attribute in &statement.attributes {
+    visitor.visit_attribute(attribute);
+  }
+}
+
+pub fn visit_node_statement_mut<'src, V: VisitMut<'src> + ?Sized>(
+  visitor: &mut V,
+  statement: &mut NodeStatement<'src>,
+) {
+  visitor.visit_node_id_mut(&mut statement.id);
+
+  for attribute in &mut statement.attributes {
+    visitor.visit_attribute_mut(attribute);
+  }
+}
+
+pub fn visit_port<'ast, 'src, V: Visit<'ast, 'src> + ?Sized>(
+  visitor: &mut V,
+  port: &'ast Port<'src>,
+) {
+  visitor.visit_id(&port.id);
+
+  if let Some(compass) = &port.compass {
+    visitor.visit_id(compass);
+  }
+}
+
+pub fn visit_port_mut<'src, V: VisitMut<'src> + ?Sized>(
+  visitor: &mut V,
+  port: &mut Port<'src>,
+) {
+  visitor.visit_id_mut(&mut port.id);
+
+  if let Some(compass) = &mut port.compass {
+    visitor.visit_id_mut(compass);
+  }
+}
+
+pub fn visit_statement<'ast, 'src, V: Visit<'ast, 'src> + ?Sized>(
+  visitor: &mut V,
+  statement: &'ast Statement<'src>,
+) {
+  match statement {
+    Statement::Assign(key, value) => {
+      visitor.visit_id(key);
+      visitor.visit_id(value);
+    }
+    Statement::Attr(statement) => visitor.visit_attribute_statement(statement),
+    Statement::Edge(statement) => visitor.visit_edge_statement(statement),
+    Statement::Node(statement) => visitor.visit_node_statement(statement),
+    Statement::Subgraph(subgraph) => visitor.visit_subgraph(subgraph),
+  }
+}
+
+pub fn visit_statement_mut<'src, V: VisitMut<'src> + ?Sized>(
+  visitor: &mut V,
+  statement: &mut Statement<'src>,
+) {
+  match statement {
+    Statement::Assign(key, value) => {
+      visitor.visit_id_mut(key);
+      visitor.visit_id_mut(value);
+    }
+    Statement::Attr(statement) => {
+      visitor.visit_attribute_statement_mut(statement);
+    }
+    Statement::Edge(statement) => visitor.visit_edge_statement_mut(statement),
+    Statement::Node(statement) => visitor.visit_node_statement_mut(statement),
+    Statement::Subgraph(subgraph) => visitor.visit_subgraph_mut(subgraph),
+  }
+}
+
+pub fn visit_subgraph<'ast, 'src, V: Visit<'ast, 'src> + ?Sized>(
+  visitor: &mut V,
+  subgraph: &'ast Subgraph<'src>,
+) {
+  if let Some(id) = &subgraph.id {
+    visitor.visit_id(id);
+  }
+
+  for statement in &subgraph.statements {
+    visitor.visit_statement(statement);
+  }
+}
+
+pub fn visit_subgraph_mut<'src, V: VisitMut<'src> + ?Sized>(
+  visitor: &mut V,
+  subgraph: &mut Subgraph<'src>,
+) {
+  if let Some(id) = &mut subgraph.id {
+    visitor.visit_id_mut(id);
+  }
+
+  for statement in &mut subgraph.statements {
+    visitor.visit_statement_mut(statement);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn fold() {
+    struct Strip;
+
+    impl<'src> Fold<'src> for Strip {
+      fn fold_node_statement(
+        &mut self,
+        statement: NodeStatement<'src>,
+      ) -> NodeStatement<'src> {
+        NodeStatement {
+          attributes: Vec::new(),
+          ..fold_node_statement(self, statement)
+        }
+      }
+
+      fn fold_port(&mut self, port: Port<'src>) -> Port<'src> {
+        Port {
+          compass: None,
+          ..port
+        }
+      }
+    }
+
+    let graph =
+      parse("digraph { a [color=red] a:p:n -> { b [shape=box] } [label=x] }")
+        .unwrap();
+
+    assert_eq!(
+      Strip.fold_graph(graph).to_string(),
+      "digraph {\n  a;\n  a:p -> { b } [label=x];\n}",
+    );
+  }
+
+  #[test]
+  fn visit() {
+    #[derive(Default)]
+    struct Collect<'ast> {
+      ids: Vec<String>,
+      subgraphs: Vec<&'ast Subgraph<'ast>>,
+    }
+
+    impl<'ast> Visit<'ast, 'ast> for Collect<'ast> {
+      fn visit_id(&mut self, id: &'ast Id<'ast>) {
+        self.ids.push(id.decoded().into_owned());
+      }
+
+      fn visit_subgraph(&mut self, subgraph: &'ast Subgraph<'ast>) {
+        self.subgraphs.push(subgraph);
+        visit_subgraph(self, subgraph);
+      }
+    }
+
+    let graph = parse(
+      "digraph G { rank=same; node [shape=box] a:p:n -> { b -> subgraph s { c } } [label=x] }",
+    )
+    .unwrap();
+
+    let mut collect = Collect::default();
+
+    collect.visit_graph(&graph);
+
+    assert_eq!(
+      collect.ids,
+      [
+        "G", "rank", "same", "shape", "box", "a", "p", "n", "b", "s", "c",
+        "label", "x",
+      ],
+    );
+
+    assert_eq!(collect.subgraphs.len(), 2);
+    assert_eq!(collect.subgraphs[1].to_string(), "subgraph s {\n  c;\n}");
+  }
+
+  #[test]
+  fn visit_mut() {
+    struct Rename;
+
+    impl VisitMut<'_> for Rename {
+      fn visit_node_id_mut(&mut self, node_id: &mut NodeId<'_>) {
+        node_id.id = Id::new(format!("node {}", node_id.id.decoded()));
+        visit_node_id_mut(self, node_id);
+      }
+
+      fn visit_port_mut(&mut self, port: &mut Port<'_>) {
+        port.id = Id::new(port.id.decoded().to_uppercase());
+      }
+    }
+
+    let mut graph =
+      parse("graph { a -- { b; subgraph { c:p } } [label=a] }").unwrap();
+
+    Rename.visit_graph_mut(&mut graph);
+
+    assert_eq!(
+      graph.to_string(),
+      "graph {\n  \"node a\" -- { \"node b\"; { \"node c\":P } } [label=a];\n}",
+    );
+  }
+}
@@ -0,0 +1,164 @@
+use {
+  serde_json::Value,
+  std::{
+    env, fs,
+    io::Write,
+    process::{self, Command, Output, Stdio},
+  },
+};
+
+/// A good graph, one with a warning, and one with an error.
+const MIXED: &str =
+  "digraph { a -> b }\ndigraph { c [colr=red] }\ngraph { d -> e }\n";
+
+fn dot(arguments: &[&str], stdin: &str) -> Output {
+  let mut child = Command::new(env!("CARGO_BIN_EXE_dot"))
+    .args(arguments)
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .unwrap();
+
+  child
+    .stdin
+    .take()
+    .unwrap()
+    .write_all(stdin.as_bytes())
+    .unwrap();
+
+  child.wait_with_output().unwrap()
+}
+
+#[test]
+fn json_messages_own_stdout() {
+  let output = dot(&["--message-format", "json", "-Tjson"], MIXED);
+
+  assert!(!output.status.success());
+
+  let messages = String::from_utf8(output.stdout)
+    .unwrap()
+    .lines()
+    .map(|line| serde_json::from_str::<Value>(line).unwrap())
+    .collect::<Vec<_>>();
+
+  assert_eq!(
+    messages
+      .iter()
+      .map(|message| (
+        message["code"].as_str().unwrap(),
+        message["severity"].as_str().unwrap(),
+      ))
+      .collect::<Vec<_>>(),
+    vec![
+      ("unknown-attribute", "warning"),
+      ("invalid-edge-operation", "error"),
+    ],
+  );
+}
+
+#[test]
+fn machine_messages_only_check() {
+  let output = dot(&["--message-format", "json"], "digraph { a -> b }");
+
+  assert!(output.status.success());
+  assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn output_file_with_machine_messages() {
+  let path = env::temp_dir().join(format!("dotgraph-cli-{}.gv", process::id()));
+
+  let output = dot(
+    &["--message-format", "json", "-o", path.to_str().unwrap()],
+    MIXED,
+  );
+
+  let graphs = fs::read_to_string(&path).unwrap();
+
+  fs::remove_file(&path).unwrap();
+
+  assert_eq!(
+    graphs,
+    "digraph {\n  a -> b;\n}\ndigraph {\n  c [colr=red];\n}\n",
+  );
+
+  assert_eq!(String::from_utf8(output.stdout).unwrap().lines().count(), 2);
+}
+
+#[test]
+fn sarif_messages_own_stdout() {
+  let output = dot(&["--message-format", "sarif"], MIXED);
+
+  assert!(!output.status.success());
+
+  let log = serde_json::from_slice::<Value>(&output.stdout).unwrap();
+
+  assert_eq!(
+    log["runs"][0]["results"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|result| result["level"].as_str().unwrap())
+      .collect::<Vec<_>>(),
+    vec!["warning", "error"],
+  );
+}
+
+#[test]
+fn unreadable_inputs_are_reported() {
+  let directory = env::temp_dir();
+
+  let missing =
+    directory.join(format!("dotgraph-missing-{}.gv", process::id()));
+  let path = directory.join(format!("dotgraph-input-{}.gv", process::id()));
+
+  fs::write(&path, "digraph { a [colr=red] }").unwrap();
+
+  let output = dot(
+    &[
+      "--message-format",
+      "sarif",
+      missing.to_str().unwrap(),
+      path.to_str().unwrap(),
+    ],
+    "",
+  );
+
+  fs::remove_file(&path).unwrap();
+
+  assert!(!output.status.success());
+
+  let log = serde_json::from_slice::<Value>(&output.stdout).unwrap();
+
+  assert_eq!(
+    log["runs"][0]["results"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|result| (
+        result["ruleId"].as_str().unwrap(),
+        result["level"].as_str().unwrap(),
+      ))
+      .collect::<Vec<_>>(),
+    vec![("io-error", "error"), ("unknown-attribute", "warning")],
+  );
+}
+
+#[test]
+fn warnings_do_not_stop_output() {
+  let output = dot(&[], "digraph { a [myattr=1] }");
+
+  assert!(output.status.success());
+
+  assert_eq!(
+    String::from_utf8(output.stdout).unwrap(),
+    "digraph {\n  a [myattr=1];\n}\n",
+  );
+
+  assert!(
+    String::from_utf8(output.stderr)
+      .unwrap()
+      .contains("unknown attribute `myattr`")
+  );
+}